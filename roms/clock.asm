@@ -0,0 +1,282 @@
+; demo firmware for the sm510: a 24-hour clock on the lcd's last six digits
+;   start/stop  hours +1
+;   reset       seconds to 00 and minutes +1
+;   alarm       beeps while it's held
+;
+; each page's code follows the program counter's own order, so a routine that outgrows its 64
+; bytes carries on elsewhere with TL. TML only calls into PM 0-3: 0x400-0x4ff and 0x800-0x8ff here.
+;
+; ram page 0 holds the time, one digit a nibble
+;   0x04 hour tens    0x06 hour units    0x08 minute tens
+;   0x0a minute units 0x0c second tens   0x0e second units
+; pages 1 and 2 hold the segments for digits 0-9, a b c d and e f g, filled in at reset.
+; lcd digit d: segments a b c d at 0x60 + 2d, e f g dp at 0x61 + 2d
+
+.equ KEYS, 0x00
+
+.equ KEY_START_STOP, 1
+.equ KEY_RESET, 2
+.equ KEY_ALARM, 3
+
+; ---- reset: lcd on, first key row strobed, segment tables into ram ----------------------------
+.org 0xdc0
+reset:
+        LAX 1
+        ATBP                    ; lcd on
+        WS                      ; strobe the first key row
+        LBL 0x10                ; a b c d for 0-9
+        LAX 0xf
+        EXCI 0
+        LAX 0x6
+        EXCI 0
+        LAX 0xb
+        EXCI 0
+        LAX 0xf
+        EXCI 0
+        LAX 0x6
+        EXCI 0
+        LAX 0xd
+        EXCI 0
+        LAX 0xd
+        EXCI 0
+        LAX 0x7
+        EXCI 0
+        LAX 0xf
+        EXCI 0
+        LAX 0xf
+        EXCI 0
+        TL high_segments
+
+.org 0xe00
+high_segments:
+        LBL 0x20                ; e f g for 0-9
+        LAX 0x3
+        EXCI 0
+        LAX 0x0
+        EXCI 0
+        LAX 0x5
+        EXCI 0
+        LAX 0x4
+        EXCI 0
+        LAX 0x6
+        EXCI 0
+        LAX 0x6
+        EXCI 0
+        LAX 0x7
+        EXCI 0
+        LAX 0x0
+        EXCI 0
+        LAX 0x7
+        EXCI 0
+        LAX 0x6
+        EXCI 0
+        TML show
+        CEND                    ; on to the main loop at the next second
+
+; ---- where CEND wakes up, once a second or when a key goes down -------------------------------
+.org 0x400
+main:
+        TIS
+        TML tick                ; only when a second went by
+        TML keys
+        TML show
+        CEND
+
+; ---- one second on, carrying through minutes and hours ----------------------------------------
+.org 0x440
+tick:
+        LBL 0x0e                ; second units
+        LAX 1
+        ADD
+        EXC 0
+        LAX 10
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        LBL 0x0c                ; second tens
+        LAX 1
+        ADD
+        EXC 0
+        LAX 6
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        TL minute               ; and on into the minutes
+
+.org 0x480
+minute:
+        LBL 0x0a                ; minute units
+        LAX 1
+        ADD
+        EXC 0
+        LAX 10
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        LBL 0x08                ; minute tens
+        LAX 1
+        ADD
+        EXC 0
+        LAX 6
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        TL hour
+
+.org 0x4c0
+hour:
+        LBL 0x06                ; hour units
+        LAX 1
+        ADD
+        EXC 0
+        LBL 0x04                ; 24 o'clock is 00
+        LAX 2
+        TAM
+        T hour_units
+        LBL 0x06
+        LAX 4
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        LBL 0x04
+        LAX 0
+        EXC 0
+        RTN0
+hour_units:
+        LBL 0x06
+        LAX 10
+        TAM
+        RTN0
+        LAX 0
+        EXC 0
+        LBL 0x04
+        LAX 1
+        ADD
+        EXC 0
+        RTN0
+
+; ---- buttons ----------------------------------------------------------------------------------
+.org 0x800
+keys:
+        KTA
+        TA0
+        T pressed
+        RTN0
+pressed:
+        LBL KEYS
+        EXC 0                   ; keep the keys in ram to test them a bit at a time
+        TMI KEY_ALARM
+        T not_alarm
+        LAX 1
+        ATR                     ; buzzer on until the key comes up
+        T release
+not_alarm:
+        TMI KEY_START_STOP
+        T not_start_stop
+        TML hour
+        T release
+not_start_stop:
+        TMI KEY_RESET
+        T release
+        LBL 0x0c
+        LAX 0
+        EXC 0
+        LBL 0x0e
+        LAX 0
+        EXC 0
+        TML minute
+release:
+        KTA                     ; wait for the key to come up so a press counts once
+        TA0
+        T release
+        LAX 0
+        ATR
+        RTN0
+
+; ---- time digits to lcd segments --------------------------------------------------------------
+; the digit goes into Bl to pick its entry from the segment table, and Bl is loaded again to put
+; the entry on the lcd; three digits fill a page
+.org 0x840
+show:
+        LBL 0x04
+        LDA 1                   ; the digit, and Bm over to the a b c d table
+        EXBLA
+        LDA 0
+        LBL 0x64
+        EXC 0
+        LBL 0x04
+        LDA 2                   ; and the e f g table
+        EXBLA
+        LDA 0
+        LBL 0x65
+        EXC 0
+        LBL 0x06
+        LDA 1
+        EXBLA
+        LDA 0
+        LBL 0x66
+        EXC 0
+        LBL 0x06
+        LDA 2
+        EXBLA
+        LDA 0
+        LBL 0x67
+        EXC 0
+        LBL 0x08
+        LDA 1
+        EXBLA
+        LDA 0
+        LBL 0x68
+        EXC 0
+        LBL 0x08
+        LDA 2
+        EXBLA
+        LDA 0
+        LBL 0x69
+        EXC 0
+        TL show_seconds
+
+.org 0x880
+show_seconds:
+        LBL 0x0a
+        LDA 1
+        EXBLA
+        LDA 0
+        LBL 0x6a
+        EXC 0
+        LBL 0x0a
+        LDA 2
+        EXBLA
+        LDA 0
+        LBL 0x6b
+        EXC 0
+        LBL 0x0c
+        LDA 1
+        EXBLA
+        LDA 0
+        LBL 0x6c
+        EXC 0
+        LBL 0x0c
+        LDA 2
+        EXBLA
+        LDA 0
+        LBL 0x6d
+        EXC 0
+        LBL 0x0e
+        LDA 1
+        EXBLA
+        LDA 0
+        LBL 0x6e
+        EXC 0
+        LBL 0x0e
+        LDA 2
+        EXBLA
+        LDA 0
+        LBL 0x6f
+        EXC 0
+        RTN0
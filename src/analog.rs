@@ -0,0 +1,51 @@
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+
+// an analog movement run by the same quartz as the lcd but set on its own with the crown
+#[derive(Debug, Clone)]
+pub struct Hands {
+    offset: TimeDelta, // how far the hands are ahead of the digital time
+    stopped_at: Option<DateTime<Local>>, // digital time when the crown was pulled out
+}
+
+impl Hands {
+    pub fn new(offset_seconds: i64) -> Self {
+        Self {
+            offset: TimeDelta::seconds(offset_seconds),
+            stopped_at: None,
+        }
+    }
+
+    // where the hands point, given the digital time
+    pub fn time(&self, now: DateTime<Local>) -> NaiveTime {
+        (self.stopped_at.unwrap_or(now) + self.offset).time()
+    }
+
+    pub fn crown_pulled(&self) -> bool {
+        self.stopped_at.is_some()
+    }
+
+    // pulling the crown stops the hands; pushing it back starts them from where they are
+    pub fn pull_crown(&mut self, now: DateTime<Local>) {
+        match self.stopped_at.take() {
+            Some(stopped_at) => self.offset = wrap(self.offset + (stopped_at - now)),
+            None => self.stopped_at = Some(now),
+        }
+    }
+
+    // each click moves the minute hand a minute, and the hour hand along with it
+    pub fn turn_crown(&mut self, minutes: i64) {
+        if self.crown_pulled() {
+            self.offset = wrap(self.offset + TimeDelta::minutes(minutes));
+        }
+    }
+
+    // what gets saved, only meaningful with the crown pushed in
+    pub fn offset_seconds(&self) -> i64 {
+        self.offset.num_seconds()
+    }
+}
+
+// past twelve hours the hands look the same, so keep the offset small
+fn wrap(offset: TimeDelta) -> TimeDelta {
+    TimeDelta::seconds(offset.num_seconds().rem_euclid(12 * 60 * 60))
+}
@@ -0,0 +1,387 @@
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mcu::{self, OPCODES, Opcode, Operand, PAGE_SIZE, ROM_SIZE};
+
+// the rom that runs when no --rom is given
+pub const DEMO_SOURCE: &str = include_str!("../roms/clock.asm");
+
+// the last PM that TL reaches and TML calls into; TL's 0x7c-0x7f are TML
+const LONG_LAST_PM: usize = 11;
+const CALL_LAST_PM: usize = 3;
+
+// one line of source once comments and labels are out of the way
+enum Statement<'a> {
+    Org(&'a str),
+    Byte(Vec<&'a str>),
+    Vector(&'a str),
+    Equ(&'a str, &'a str),
+    Instruction(&'static Opcode, Option<&'a str>),
+}
+
+// line number, label, statement
+type SourceLine<'a> = (usize, Option<&'a str>, Option<Statement<'a>>);
+
+// a rom image as it is, or assembly source if the file ends in .asm
+pub fn load(path: &Path) -> Result<Vec<u8>> {
+    if path.extension().is_some_and(|ext| ext == "asm") {
+        let source = fs::read_to_string(path)?;
+        return assemble(&source).with_context(|| format!("can't assemble {}", path.display()));
+    }
+    let rom = fs::read(path)?;
+    if rom.len() > ROM_SIZE {
+        bail!(
+            "{} is {} bytes, the rom only holds {}",
+            path.display(),
+            rom.len(),
+            ROM_SIZE
+        );
+    }
+    Ok(rom)
+}
+
+// writes foo.asm out as foo.bin and says where it went
+pub fn assemble_file(path: &Path) -> Result<PathBuf> {
+    let source = fs::read_to_string(path)?;
+    let rom = assemble(&source).with_context(|| format!("can't assemble {}", path.display()))?;
+    let output = path.with_extension("bin");
+    fs::write(&output, rom)?;
+    Ok(output)
+}
+
+// where the next byte goes: instructions follow the program counter round its page, while
+// .byte data is laid out in plain address order for TM's vectors and the like
+struct Cursor {
+    address: usize,
+    start: usize, // where the page was entered, which the program counter comes back to
+    full: bool,
+}
+
+impl Cursor {
+    fn at(address: usize) -> Self {
+        Self {
+            address,
+            start: address,
+            full: false,
+        }
+    }
+
+    // the address for the next instruction byte, then steps past it
+    fn code(&mut self) -> Result<usize> {
+        if self.full {
+            bail!(
+                "page {:#05x} is full, carry on in another page with TL",
+                self.start / PAGE_SIZE * PAGE_SIZE
+            );
+        }
+        let address = self.address;
+        self.address = mcu::next_pc(address as u16) as usize;
+        self.full = self.address == self.start;
+        Ok(address)
+    }
+
+    fn data(&mut self) -> usize {
+        let address = self.address;
+        self.address += 1;
+        self.start = self.address;
+        self.full = false;
+        address
+    }
+}
+
+// two passes: the first finds where every label lands, the second writes the bytes
+pub fn assemble(source: &str) -> Result<Vec<u8>> {
+    let statements = parse(source)?;
+
+    let mut symbols: HashMap<String, usize> = HashMap::new();
+    let mut cursor = Cursor::at(mcu::RESET_VECTOR as usize);
+    for (number, label, statement) in &statements {
+        let at_line = |error: anyhow::Error| anyhow!("line {}: {}", number, error);
+        if let Some(label) = label
+            && symbols.insert(label.to_string(), cursor.address).is_some()
+        {
+            return Err(at_line(anyhow!("'{}' is defined twice", label)));
+        }
+        match statement {
+            Some(Statement::Org(value)) => {
+                let value = value_of(value, &symbols).map_err(at_line)?;
+                cursor = Cursor::at(in_range(value, ROM_SIZE - 1).map_err(at_line)?);
+            }
+            Some(Statement::Equ(name, value)) => {
+                let value = value_of(value, &symbols).map_err(at_line)?;
+                if symbols.insert(name.to_string(), value).is_some() {
+                    return Err(at_line(anyhow!("'{}' is defined twice", name)));
+                }
+            }
+            Some(Statement::Byte(values)) => {
+                for _ in values.iter() {
+                    cursor.data();
+                }
+            }
+            Some(Statement::Vector(_)) => {
+                cursor.data();
+            }
+            Some(Statement::Instruction(opcode, _)) => {
+                for _ in 0..size(opcode) {
+                    cursor.code().map_err(at_line)?;
+                }
+            }
+            None => {}
+        }
+    }
+
+    let mut rom = vec![0; ROM_SIZE];
+    let mut written = vec![false; ROM_SIZE];
+    let mut end = 0;
+    let mut cursor = Cursor::at(mcu::RESET_VECTOR as usize);
+    for (number, _, statement) in &statements {
+        let at_line = |error: anyhow::Error| anyhow!("line {}: {}", number, error);
+        let placed = match statement {
+            Some(Statement::Org(value)) => {
+                cursor = Cursor::at(value_of(value, &symbols).map_err(at_line)?);
+                continue;
+            }
+            Some(Statement::Byte(values)) => values
+                .iter()
+                .map(|value| {
+                    let byte = in_range(value_of(value, &symbols)?, 0xff)? as u8;
+                    Ok((cursor.data(), byte))
+                })
+                .collect::<Result<Vec<_>>>()
+                .map_err(at_line)?,
+            Some(Statement::Vector(value)) => {
+                let byte = vector(value_of(value, &symbols).map_err(at_line)?).map_err(at_line)?;
+                vec![(cursor.data(), byte)]
+            }
+            Some(Statement::Instruction(opcode, operand)) => {
+                let bytes = encode(opcode, *operand, cursor.address, &symbols).map_err(at_line)?;
+                bytes
+                    .into_iter()
+                    .map(|byte| Ok((cursor.code()?, byte)))
+                    .collect::<Result<Vec<_>>>()
+                    .map_err(at_line)?
+            }
+            Some(Statement::Equ(..)) | None => continue,
+        };
+        for (address, byte) in placed {
+            if address >= ROM_SIZE {
+                return Err(at_line(anyhow!(
+                    "past the end of the {} byte rom",
+                    ROM_SIZE
+                )));
+            }
+            if written[address] {
+                return Err(at_line(anyhow!("overwrites address {:#05x}", address)));
+            }
+            rom[address] = byte;
+            written[address] = true;
+            end = end.max(address + 1);
+        }
+    }
+    rom.truncate(end);
+    Ok(rom)
+}
+
+fn parse(source: &str) -> Result<Vec<SourceLine<'_>>> {
+    let mut statements = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let mut text = line.split(';').next().unwrap_or("").trim();
+        let mut label = None;
+        if let Some((name, rest)) = text.split_once(':') {
+            let name = name.trim();
+            if !is_symbol(name) {
+                bail!("line {}: '{}' isn't a valid label", number, name);
+            }
+            label = Some(name);
+            text = rest.trim();
+        }
+
+        let statement = if text.is_empty() {
+            None
+        } else {
+            let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+            let operands: Vec<&str> = rest
+                .split(',')
+                .map(str::trim)
+                .filter(|operand| !operand.is_empty())
+                .collect();
+            Some(statement(word, operands).map_err(|error| anyhow!("line {}: {}", number, error))?)
+        };
+        if label.is_some() || statement.is_some() {
+            statements.push((number, label, statement));
+        }
+    }
+    Ok(statements)
+}
+
+fn statement<'a>(word: &str, operands: Vec<&'a str>) -> Result<Statement<'a>> {
+    let word = word.to_uppercase();
+    match (word.as_str(), operands.as_slice()) {
+        (".ORG", [value]) => return Ok(Statement::Org(value)),
+        (".EQU", [name, value]) if is_symbol(name) => return Ok(Statement::Equ(name, value)),
+        (".BYTE", values) if !values.is_empty() => return Ok(Statement::Byte(operands)),
+        (".VECTOR", [value]) => return Ok(Statement::Vector(value)),
+        (".ORG" | ".EQU" | ".BYTE" | ".VECTOR", _) => {
+            bail!("bad operands for {}", word.to_lowercase())
+        }
+        _ => {}
+    }
+
+    let opcode = OPCODES
+        .iter()
+        .find(|opcode| opcode.mnemonic == word)
+        .ok_or_else(|| anyhow!("unknown instruction '{}'", word))?;
+    match (opcode.operand, operands.as_slice()) {
+        (Operand::None, []) => Ok(Statement::Instruction(opcode, None)),
+        (Operand::None, _) => bail!("{} takes no operand", word),
+        (_, [operand]) => Ok(Statement::Instruction(opcode, Some(operand))),
+        (_, _) => bail!("{} takes one operand", word),
+    }
+}
+
+fn size(opcode: &Opcode) -> usize {
+    if mcu::long(opcode.base) { 2 } else { 1 }
+}
+
+fn encode(
+    opcode: &Opcode,
+    operand: Option<&str>,
+    address: usize,
+    symbols: &HashMap<String, usize>,
+) -> Result<Vec<u8>> {
+    let value = match operand {
+        Some(operand) => value_of(operand, symbols)?,
+        None => 0,
+    };
+    let byte = |value: usize| opcode.base | value as u8;
+    Ok(match opcode.operand {
+        Operand::None => vec![opcode.base],
+        Operand::Bit | Operand::Bm => vec![byte(in_range(value, 3)?)],
+        Operand::Nibble => vec![byte(in_range(value, 15)?)],
+        Operand::Vector => vec![byte(in_range(value, PAGE_SIZE - 1)?)],
+        Operand::Ram => vec![opcode.base, in_range(value, 0x7f)? as u8],
+        Operand::Short => {
+            let page = address / PAGE_SIZE;
+            if value / PAGE_SIZE != page {
+                bail!(
+                    "{:#05x} is outside page {:#05x}-{:#05x}, use TL",
+                    value,
+                    page * PAGE_SIZE,
+                    page * PAGE_SIZE + PAGE_SIZE - 1
+                );
+            }
+            vec![byte(value % PAGE_SIZE)]
+        }
+        // PU and PL go in the second byte, PM in the opcode
+        Operand::Long | Operand::Call => {
+            let value = in_range(value, ROM_SIZE - 1)?;
+            let pm = value / PAGE_SIZE % 16;
+            let last = match opcode.operand {
+                Operand::Long => LONG_LAST_PM,
+                _ => CALL_LAST_PM,
+            };
+            if pm > last {
+                bail!(
+                    "{:#05x} is in PM {}, {} only reaches PM 0-{}",
+                    value,
+                    pm,
+                    opcode.mnemonic,
+                    last
+                );
+            }
+            let second = (value >> 10 << 6) | (value % PAGE_SIZE);
+            vec![byte(pm), second as u8]
+        }
+    })
+}
+
+// the byte TM reads to reach a label, which has to be in PM 4
+fn vector(value: usize) -> Result<u8> {
+    let value = in_range(value, ROM_SIZE - 1)?;
+    let pm = value / PAGE_SIZE % 16;
+    if pm != mcu::TM_PM as usize {
+        bail!(
+            "{:#05x} is in PM {}, TM only reaches PM {}",
+            value,
+            pm,
+            mcu::TM_PM
+        );
+    }
+    Ok(((value >> 10 << 6) | (value % PAGE_SIZE)) as u8)
+}
+
+// decimal, 0x hex, 0b binary, or a label or .equ name
+fn value_of(text: &str, symbols: &HashMap<String, usize>) -> Result<usize> {
+    let parsed = if let Some(hex) = text.strip_prefix("0x") {
+        usize::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = text.strip_prefix("0b") {
+        usize::from_str_radix(binary, 2).ok()
+    } else {
+        text.parse().ok()
+    };
+    parsed
+        .or_else(|| symbols.get(text).copied())
+        .ok_or_else(|| anyhow!("unknown value '{}'", text))
+}
+
+fn in_range(value: usize, max: usize) -> Result<usize> {
+    if value > max {
+        bail!("{} is out of range, the most is {}", value, max);
+    }
+    Ok(value)
+}
+
+fn is_symbol(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> String {
+        format!("{:#}", assemble(source).unwrap_err())
+    }
+
+    #[test]
+    fn instructions_follow_the_program_counter() {
+        let rom = assemble(".org 0x400\n LAX 1\n LBL 0x12\n LAX 2").unwrap();
+        let second = mcu::next_pc(0x400) as usize;
+        assert_eq!(second, 0x420);
+        assert_eq!(rom[0x400], 0x21);
+        assert_eq!(rom[second], 0x5f);
+        assert_eq!(rom[mcu::next_pc(second as u16) as usize], 0x12);
+        // .byte stays in address order
+        let rom = assemble(".org 0x400\n .byte 1, 2").unwrap();
+        assert_eq!(&rom[0x400..], [1, 2]);
+    }
+
+    #[test]
+    fn a_page_holds_63_instructions() {
+        let page = format!(".org 0x400\n{}", "SKIP\n".repeat(63));
+        assert!(assemble(&page).is_ok());
+        assert!(error(&format!("{}SKIP", page)).contains("page 0x400 is full"));
+    }
+
+    #[test]
+    fn branches_stay_in_reach() {
+        assert!(error(".org 0x400\n T there\n .org 0x440\n there: SKIP").contains("outside page"));
+        // PM 4 is past TML's reach and PM 12 past TL's
+        assert!(error("TML there\n .org 0x100\n there: SKIP").contains("TML only reaches PM 0-3"));
+        assert!(error("TL there\n .org 0x300\n there: SKIP").contains("TL only reaches PM 0-11"));
+        assert!(assemble("TL there\n .org 0x2c0\n there: SKIP").is_ok());
+        assert!(error(".vector there\n .org 0x140\n there: SKIP").contains("TM only reaches PM 4"));
+    }
+
+    #[test]
+    fn long_jumps_carry_pu_and_pl() {
+        let rom = assemble("TL there\n .org 0xe05\n there: SKIP").unwrap();
+        let at = mcu::RESET_VECTOR as usize;
+        assert_eq!(rom[at], 0x78);
+        assert_eq!(rom[mcu::next_pc(at as u16) as usize], 3 << 6 | 5);
+    }
+}
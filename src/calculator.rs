@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+// digits shown on the lcd, not counting the sign or decimal point
+pub const DIGITS: usize = 8;
+
+// the sixteen keys on a calculator watch's keypad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CalcKey {
+    Digit(u8),
+    Point,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Equals,
+}
+
+impl CalcKey {
+    pub const ALL: [CalcKey; 16] = [
+        CalcKey::Digit(0),
+        CalcKey::Digit(1),
+        CalcKey::Digit(2),
+        CalcKey::Digit(3),
+        CalcKey::Digit(4),
+        CalcKey::Digit(5),
+        CalcKey::Digit(6),
+        CalcKey::Digit(7),
+        CalcKey::Digit(8),
+        CalcKey::Digit(9),
+        CalcKey::Point,
+        CalcKey::Add,
+        CalcKey::Subtract,
+        CalcKey::Multiply,
+        CalcKey::Divide,
+        CalcKey::Equals,
+    ];
+
+    pub fn name(self) -> &'static str {
+        const DIGIT_NAMES: [&str; 10] = [
+            "key_0", "key_1", "key_2", "key_3", "key_4", "key_5", "key_6", "key_7", "key_8",
+            "key_9",
+        ];
+        match self {
+            CalcKey::Digit(digit) => DIGIT_NAMES[digit as usize % 10],
+            CalcKey::Point => "key_point",
+            CalcKey::Add => "key_add",
+            CalcKey::Subtract => "key_subtract",
+            CalcKey::Multiply => "key_multiply",
+            CalcKey::Divide => "key_divide",
+            CalcKey::Equals => "key_equals",
+        }
+    }
+
+    // what's printed on the key
+    pub fn symbol(self) -> char {
+        match self {
+            CalcKey::Digit(digit) => char::from(b'0' + digit),
+            CalcKey::Point => '.',
+            CalcKey::Add => '+',
+            CalcKey::Subtract => '-',
+            CalcKey::Multiply => '×',
+            CalcKey::Divide => '÷',
+            CalcKey::Equals => '=',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    pub fn symbol(self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '×',
+            Operator::Divide => '÷',
+        }
+    }
+
+    fn apply(self, left: f64, right: f64) -> Option<f64> {
+        match self {
+            Operator::Add => Some(left + right),
+            Operator::Subtract => Some(left - right),
+            Operator::Multiply => Some(left * right),
+            Operator::Divide if right == 0.0 => None,
+            Operator::Divide => Some(left / right),
+        }
+    }
+}
+
+// an 8-digit casio-style calculator: chained operators, constant calculation on
+// repeated "=" and a locked "E" display on overflow or division by zero
+#[derive(Debug, Clone, Default)]
+pub struct Calculator {
+    accumulator: f64,
+    entry: String, // digits typed so far, empty when showing the accumulator
+    operator: Option<Operator>,
+    constant: Option<(Operator, f64)>, // what a bare "=" repeats
+    error: Option<String>,             // digits shown next to the "E" while locked
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn press(&mut self, key: CalcKey) {
+        if self.error.is_some() {
+            return;
+        }
+
+        match key {
+            CalcKey::Digit(digit) => self.enter_digit(digit),
+            CalcKey::Point => {
+                if self.entry.is_empty() {
+                    self.entry.push('0');
+                }
+                if !self.entry.contains('.') {
+                    self.entry.push('.');
+                }
+            }
+            CalcKey::Add => self.enter_operator(Operator::Add),
+            CalcKey::Subtract => self.enter_operator(Operator::Subtract),
+            CalcKey::Multiply => self.enter_operator(Operator::Multiply),
+            CalcKey::Divide => self.enter_operator(Operator::Divide),
+            CalcKey::Equals => self.equals(),
+        }
+    }
+
+    // first press clears the entry, the next clears everything including "E"
+    pub fn clear(&mut self) {
+        if self.entry.is_empty() || self.error.is_some() {
+            *self = Self::new();
+        } else {
+            self.entry.clear();
+        }
+    }
+
+    pub fn display(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        if !self.entry.is_empty() {
+            return self.entry.clone();
+        }
+        format_number(self.accumulator).unwrap_or_else(|| "0".to_string())
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub fn operator(&self) -> Option<Operator> {
+        self.operator
+    }
+
+    // the "K" indicator on the lcd
+    pub fn has_constant(&self) -> bool {
+        self.constant.is_some() && self.operator.is_none() && self.error.is_none()
+    }
+
+    fn enter_digit(&mut self, digit: u8) {
+        let digits = self.entry.chars().filter(char::is_ascii_digit).count();
+        if digits >= DIGITS {
+            return;
+        }
+        // a leading zero is replaced rather than kept
+        if self.entry == "0" {
+            self.entry.clear();
+        }
+        self.entry.push(char::from(b'0' + digit));
+    }
+
+    fn entry_value(&self) -> Option<f64> {
+        if self.entry.is_empty() {
+            None
+        } else {
+            self.entry.parse().ok()
+        }
+    }
+
+    fn enter_operator(&mut self, operator: Operator) {
+        if let Some(value) = self.entry_value() {
+            match self.operator {
+                Some(pending) => self.calculate(pending, self.accumulator, value),
+                None => self.accumulator = value,
+            }
+            self.entry.clear();
+        }
+        // pressing another operator straight away just replaces the first
+        if self.error.is_none() {
+            self.operator = Some(operator);
+        }
+    }
+
+    fn equals(&mut self) {
+        let entry = self.entry_value();
+        self.entry.clear();
+
+        if let Some(operator) = self.operator.take() {
+            // "5 + =" uses the displayed number as the second operand
+            let right = entry.unwrap_or(self.accumulator);
+            let left = self.accumulator;
+            // multiplication keeps the first operand as the constant, the rest keep the second
+            self.constant = Some(match operator {
+                Operator::Multiply => (operator, left),
+                _ => (operator, right),
+            });
+            self.calculate(operator, left, right);
+        } else if let Some((operator, constant)) = self.constant {
+            let value = entry.unwrap_or(self.accumulator);
+            match operator {
+                Operator::Multiply => self.calculate(operator, constant, value),
+                _ => self.calculate(operator, value, constant),
+            }
+        } else if let Some(value) = entry {
+            self.accumulator = value;
+        }
+    }
+
+    fn calculate(&mut self, operator: Operator, left: f64, right: f64) {
+        match operator.apply(left, right) {
+            // keep only what fits on the lcd, the same as the watch's registers
+            Some(result) => match format_number(result) {
+                Some(text) => self.accumulator = text.parse().unwrap_or(result),
+                None => self.overflow(result),
+            },
+            None => self.error = Some("0".to_string()),
+        }
+    }
+
+    // like the real thing, show the leading digits with the point moved 8 places
+    fn overflow(&mut self, result: f64) {
+        let scaled = result / 10f64.powi(DIGITS as i32);
+        self.error = Some(format_number(scaled).unwrap_or_else(|| "0".to_string()));
+    }
+}
+
+// truncates to fit 8 digits, returns None if the integer part doesn't fit
+pub fn format_number(value: f64) -> Option<String> {
+    let magnitude = value.abs();
+    if magnitude >= 10f64.powi(DIGITS as i32) {
+        return None;
+    }
+
+    let integer_digits = (magnitude.trunc() as u64).to_string().len();
+    let decimals = DIGITS - integer_digits;
+    let scale = 10f64.powi(decimals as i32);
+    // the tiny nudge stops 0.1 + 0.2 from truncating to 0.2999999
+    let truncated = (magnitude * scale + 1e-6).trunc() / scale;
+
+    let mut text = format!("{:.*}", decimals, truncated);
+    if text.contains('.') {
+        text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if value < 0.0 && text != "0" {
+        text.insert(0, '-');
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(keys: &str) -> Calculator {
+        let mut calculator = Calculator::new();
+        for key in keys.chars() {
+            calculator.press(match key {
+                '0'..='9' => CalcKey::Digit(key as u8 - b'0'),
+                '.' => CalcKey::Point,
+                '+' => CalcKey::Add,
+                '-' => CalcKey::Subtract,
+                '*' => CalcKey::Multiply,
+                '/' => CalcKey::Divide,
+                '=' => CalcKey::Equals,
+                _ => panic!("no key for {}", key),
+            });
+        }
+        calculator
+    }
+
+    #[test]
+    fn overflow_locks_with_leading_digits() {
+        let mut calculator = run("99999999*3=");
+        assert!(calculator.is_error());
+        assert_eq!(calculator.display(), "2.9999999");
+        // nothing but clear gets through
+        calculator.press(CalcKey::Digit(5));
+        assert_eq!(calculator.display(), "2.9999999");
+        calculator.clear();
+        assert!(!calculator.is_error());
+        assert_eq!(calculator.display(), "0");
+    }
+
+    #[test]
+    fn repeated_equals_uses_the_constant() {
+        let calculator = run("2+3===");
+        assert_eq!(calculator.display(), "11");
+        assert!(calculator.has_constant());
+        // multiplication keeps the first operand instead
+        assert_eq!(run("3*4==").display(), "36");
+        // a new entry goes through the same constant
+        assert_eq!(run("10-4=7=").display(), "3");
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        let calculator = run("5/0=");
+        assert!(calculator.is_error());
+        assert_eq!(calculator.display(), "0");
+    }
+
+    #[test]
+    fn digits_are_truncated_to_the_lcd() {
+        assert_eq!(run("123456789").display(), "12345678");
+        assert_eq!(run("2/3=").display(), "0.6666666");
+        assert_eq!(run("0.1+0.2=").display(), "0.3");
+        assert_eq!(format_number(-12.5), Some("-12.5".to_string()));
+        assert_eq!(format_number(1e8), None);
+    }
+}
@@ -0,0 +1,288 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
+use clap::Parser;
+use std::path::PathBuf;
+
+use crate::radio;
+use crate::settings;
+use crate::watch::WatchModel;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Casio watch emulator for the terminal")]
+pub struct Cli {
+    /// Watch model to emulate (see --list-models); without it a gallery to pick from
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// List the available watch models and exit
+    #[arg(long)]
+    pub list_models: bool,
+
+    /// Settings file to load and save
+    #[arg(short, long, default_value = settings::DEFAULT_PATH)]
+    pub config: PathBuf,
+
+    /// Settings profile to use, created if it doesn't exist
+    #[arg(short, long, default_value = settings::DEFAULT_PROFILE)]
+    pub profile: String,
+
+    /// Starting watch time, e.g. 2024-05-01T06:59:50 or 23:59:55
+    #[arg(short, long, value_parser = parse_time)]
+    pub time: Option<DateTime<Local>>,
+
+    /// How fast watch time runs compared to real time
+    #[arg(short, long, value_parser = parse_speed, default_value_t = 1.0)]
+    pub speed: f64,
+
+    /// Recorded time signal for radio-controlled models (default: generated from the system clock)
+    #[arg(long)]
+    pub signal: Option<PathBuf>,
+
+    /// Fraction of generated time-signal pulses lost to noise, from 0 to 1
+    #[arg(long, value_parser = parse_noise, default_value_t = radio::DEFAULT_NOISE)]
+    pub signal_noise: f64,
+
+    /// Sensor trace for the Pro Trek, CSV or JSON (default: a generated hike)
+    #[arg(long)]
+    pub sensors: Option<PathBuf>,
+
+    /// Append infrared codes sent by the CMD-40 to this file in LIRC mode2 format, or - for stdout
+    #[arg(long)]
+    pub ir_output: Option<PathBuf>,
+
+    /// LIRC file (lircd.conf raw codes or mode2) for the CMD-40's learn mode
+    #[arg(long)]
+    pub ir_learn: Option<PathBuf>,
+
+    /// ROM image for the firmware model, or .asm source to assemble (default: a demo clock)
+    #[arg(long)]
+    pub rom: Option<PathBuf>,
+
+    /// Assemble a ROM source file into a .bin image next to it and exit
+    #[arg(long, value_name = "SOURCE")]
+    pub assemble: Option<PathBuf>,
+
+    /// Several watches side by side, as MODEL[@HOURS][/PROFILE], e.g. f91w,ae1200@+9,gd8/alice
+    #[arg(long, value_delimiter = ',', value_parser = parse_dashboard_watch)]
+    pub dashboard: Vec<DashboardWatch>,
+}
+
+// one watch on the dashboard
+#[derive(Debug, Clone)]
+pub struct DashboardWatch {
+    pub model: String,           // looked up like --model
+    pub offset: TimeDelta,       // ahead of --time, for a watch set to another city
+    pub profile: Option<String>, // instead of --profile
+}
+
+impl DashboardWatch {
+    // the single watch shown without --dashboard
+    pub fn only(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            offset: TimeDelta::zero(),
+            profile: None,
+        }
+    }
+}
+
+// names can only be checked after the definitions load, so this runs after the arguments are parsed
+pub fn parse_model(name: &str) -> Result<WatchModel> {
+    WatchModel::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = WatchModel::available()
+            .iter()
+            .map(|model| model.name())
+            .collect();
+        anyhow!(
+            "unknown model '{}', expected one of: {}",
+            name,
+            names.join(", ")
+        )
+    })
+}
+
+fn parse_dashboard_watch(text: &str) -> Result<DashboardWatch> {
+    let (text, profile) = match text.split_once('/') {
+        Some((_, "")) => return Err(anyhow!("'{}' has an empty profile name", text)),
+        Some((text, profile)) => (text, Some(profile.to_string())),
+        None => (text, None),
+    };
+    let (name, offset) = match text.split_once('@') {
+        Some((name, offset)) => (name, parse_offset(offset)?),
+        None => (text, TimeDelta::zero()),
+    };
+    Ok(DashboardWatch {
+        model: name.to_string(),
+        offset,
+        profile,
+    })
+}
+
+// hours ahead, with optional minutes: +9, -5, +5:30
+fn parse_offset(text: &str) -> Result<TimeDelta> {
+    let error = || anyhow!("'{}' is not an offset in hours like +9 or -3:30", text);
+    let (sign, rest) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    if !hours
+        .chars()
+        .chain(minutes.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(error());
+    }
+    let hours: i64 = hours.parse().map_err(|_| error())?;
+    let minutes: i64 = minutes.parse().map_err(|_| error())?;
+    // no time zone is further than 14 hours from utc
+    if minutes >= 60 || hours * 60 + minutes > 14 * 60 {
+        return Err(error());
+    }
+    Ok(TimeDelta::minutes(sign * (hours * 60 + minutes)))
+}
+
+// full rfc 3339, a local date and time, or just a time of day today
+fn parse_time(text: &str) -> Result<DateTime<Local>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.with_timezone(&Local));
+    }
+
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            ["%H:%M:%S", "%H:%M"]
+                .iter()
+                .find_map(|format| NaiveTime::parse_from_str(text, format).ok())
+                .map(|time| Local::now().date_naive().and_time(time))
+        })
+        .ok_or_else(|| anyhow!("'{}' is not an ISO 8601 date/time", text))?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow!("'{}' does not exist in the local time zone", text))
+}
+
+// a day goes by in under a second, more than enough to watch the date roll over
+const MAX_SPEED: f64 = 100_000.0;
+
+fn parse_speed(text: &str) -> Result<f64> {
+    let speed: f64 = text
+        .parse()
+        .map_err(|_| anyhow!("'{}' is not a number", text))?;
+    if speed > 0.0 && speed <= MAX_SPEED {
+        Ok(speed)
+    } else {
+        Err(anyhow!(
+            "speed must be greater than zero and at most {}",
+            MAX_SPEED
+        ))
+    }
+}
+
+fn parse_noise(text: &str) -> Result<f64> {
+    let noise: f64 = text
+        .parse()
+        .map_err(|_| anyhow!("'{}' is not a number", text))?;
+    if (0.0..=1.0).contains(&noise) {
+        Ok(noise)
+    } else {
+        Err(anyhow!("noise must be between 0 and 1"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_in_hours_and_minutes() {
+        for (text, minutes) in [
+            ("9", 540),
+            ("+9", 540),
+            ("-3:30", -210),
+            ("+5:45", 345),
+            ("+14", 840),
+            ("-14", -840),
+            ("0", 0),
+        ] {
+            assert_eq!(
+                parse_offset(text).unwrap(),
+                TimeDelta::minutes(minutes),
+                "{}",
+                text
+            );
+        }
+        for text in [
+            "", "+", "+15", "-14:30", "+14:01", "+5:60", "+5:", "9h", "+-9", "1.5",
+        ] {
+            assert!(parse_offset(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn times_with_and_without_a_date() {
+        let time = parse_time("2026-10-18T07:05:00Z").unwrap();
+        assert_eq!(
+            time.to_utc(),
+            "2026-10-18T07:05:00Z"
+                .parse::<DateTime<chrono::Utc>>()
+                .unwrap()
+        );
+        let expected = NaiveDateTime::parse_from_str("2026-10-18 07:05", "%Y-%m-%d %H:%M").unwrap();
+        for text in [
+            "2026-10-18T07:05",
+            "2026-10-18T07:05:00",
+            "2026-10-18 07:05:00",
+        ] {
+            assert_eq!(
+                parse_time(text).unwrap().naive_local(),
+                expected,
+                "{}",
+                text
+            );
+        }
+        let today = parse_time("07:05").unwrap();
+        assert_eq!(today.date_naive(), Local::now().date_naive());
+        assert_eq!(today.time(), NaiveTime::from_hms_opt(7, 5, 0).unwrap());
+        for text in ["", "tomorrow", "2026-13-01T00:00", "25:00", "2026-10-18"] {
+            assert!(parse_time(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn speed_is_positive_finite_and_capped() {
+        assert_eq!(parse_speed("1").unwrap(), 1.0);
+        assert_eq!(parse_speed("0.5").unwrap(), 0.5);
+        assert_eq!(parse_speed("100000").unwrap(), MAX_SPEED);
+        for text in ["0", "-1", "100001", "NaN", "inf", "-inf", "fast", ""] {
+            assert!(parse_speed(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn dashboard_watches() {
+        let watch = parse_dashboard_watch("f91w@+9/alice").unwrap();
+        assert_eq!(watch.model, "f91w");
+        assert_eq!(watch.offset, TimeDelta::hours(9));
+        assert_eq!(watch.profile.as_deref(), Some("alice"));
+        assert!(parse_dashboard_watch("f91w/").is_err());
+        assert!(parse_dashboard_watch("f91w@+99").is_err());
+    }
+
+    #[test]
+    fn unknown_model_lists_the_known_ones() {
+        assert_eq!(parse_model("F-91W").unwrap(), WatchModel::F91W);
+        let error = parse_model("casio").unwrap_err().to_string();
+        assert!(
+            error.starts_with("unknown model 'casio', expected one of: "),
+            "{}",
+            error
+        );
+        for model in WatchModel::ALL {
+            assert!(error.contains(model.name()), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+pub const RECORDS: usize = 30;
+pub const NAME_LENGTH: usize = 8;
+pub const NUMBER_LENGTH: usize = 12;
+
+// characters in the order the buttons step through them, starting from blank
+const NAME_CHARS: &[char] = &[
+    ' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
+    'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '-',
+    '.', '\'', '&', '/',
+];
+const NUMBER_CHARS: &[char] = &[' ', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '-'];
+
+// one telememo entry, stored without trailing blanks
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Record {
+    pub name: String,
+    pub number: String,
+}
+
+impl Record {
+    fn is_blank(&self) -> bool {
+        self.name.trim().is_empty() && self.number.trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Name,
+    Number,
+}
+
+// a record being typed in, one character at a time
+#[derive(Debug, Clone)]
+pub struct Editor {
+    name: Vec<char>,
+    number: Vec<char>,
+    pub cursor: usize,        // name positions first, then the number
+    original: Option<Record>, // the record being changed, None for a new one
+}
+
+impl Editor {
+    fn new(record: Option<&Record>) -> Self {
+        let pad = |text: &str, length: usize| {
+            let mut chars: Vec<char> = text.chars().take(length).collect();
+            chars.resize(length, ' ');
+            chars
+        };
+        let blank = Record::default();
+        let record_or_blank = record.unwrap_or(&blank);
+        Self {
+            name: pad(&record_or_blank.name, NAME_LENGTH),
+            number: pad(&record_or_blank.number, NUMBER_LENGTH),
+            cursor: 0,
+            original: record.cloned(),
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        if self.cursor < NAME_LENGTH {
+            Field::Name
+        } else {
+            Field::Number
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.iter().collect()
+    }
+
+    pub fn number(&self) -> String {
+        self.number.iter().collect()
+    }
+
+    // the character under the cursor moves one place through the character set
+    pub fn step(&mut self, forward: bool) {
+        let (slot, chars) = match self.field() {
+            Field::Name => (&mut self.name[self.cursor], NAME_CHARS),
+            Field::Number => (&mut self.number[self.cursor - NAME_LENGTH], NUMBER_CHARS),
+        };
+        let index = chars.iter().position(|c| c == slot).unwrap_or(0);
+        let next = if forward {
+            (index + 1) % chars.len()
+        } else {
+            (index + chars.len() - 1) % chars.len()
+        };
+        *slot = chars[next];
+    }
+
+    // past the last digit the cursor wraps back to the start of the name
+    pub fn advance(&mut self) {
+        self.cursor = (self.cursor + 1) % (NAME_LENGTH + NUMBER_LENGTH);
+    }
+
+    fn record(&self) -> Record {
+        Record {
+            name: self.name().trim_end().to_string(),
+            number: self.number().trim_end().to_string(),
+        }
+    }
+}
+
+// browsing and input state for telememo mode; the records themselves live in the settings
+#[derive(Debug, Clone, Default)]
+pub struct Telememo {
+    pub index: usize, // position in the sorted records, one past the end for a new entry
+    pub editor: Option<Editor>,
+}
+
+impl Telememo {
+    pub fn current<'a>(&self, records: &'a [Record]) -> Option<&'a Record> {
+        records.get(self.index)
+    }
+
+    pub fn next(&mut self, records: &[Record]) {
+        self.index = (self.index.min(records.len()) + 1) % (records.len() + 1);
+    }
+
+    pub fn previous(&mut self, records: &[Record]) {
+        let screens = records.len() + 1;
+        self.index = (self.index.min(records.len()) + screens - 1) % screens;
+    }
+
+    // the new entry screen can't be edited once the memory is full
+    pub fn edit(&mut self, records: &[Record]) {
+        let record = self.current(records);
+        if record.is_some() || records.len() < RECORDS {
+            self.editor = Some(Editor::new(record));
+        }
+    }
+
+    // stores the edited record in alphabetical order; a blanked out record is deleted
+    pub fn commit(&mut self, records: &mut Vec<Record>) {
+        let Some(editor) = self.editor.take() else {
+            return;
+        };
+        if let Some(original) = &editor.original
+            && let Some(position) = records.iter().position(|record| record == original)
+        {
+            records.remove(position);
+        }
+
+        let record = editor.record();
+        if record.is_blank() {
+            self.index = self.index.min(records.len());
+            return;
+        }
+        // saving a copy of a record already there just goes back to it
+        self.index = match records.binary_search(&record) {
+            Ok(position) => position,
+            Err(position) => {
+                records.insert(position, record);
+                position
+            }
+        };
+    }
+}
+
+pub const APPOINTMENTS: usize = 50;
+pub const MEMOS: usize = 10;
+pub const MEMO_LENGTH: usize = 24;
+const APPOINTMENT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// one scheduler entry; the time is stored as text so the list sorts by it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Appointment {
+    pub time: String, // YYYY-MM-DD HH:MM
+    pub text: String,
+}
+
+impl Appointment {
+    pub fn new(time: NaiveDateTime, text: &str) -> Self {
+        Self {
+            time: time.format(APPOINTMENT_FORMAT).to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn time(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.time, APPOINTMENT_FORMAT).ok()
+    }
+
+    pub fn due(&self, now: NaiveDateTime) -> bool {
+        self.time == now.format(APPOINTMENT_FORMAT).to_string()
+    }
+}
+
+// browsing state for the scheduler; the appointments themselves live in the settings
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    pub index: usize,
+}
+
+impl Scheduler {
+    pub fn current<'a>(&self, appointments: &'a [Appointment]) -> Option<&'a Appointment> {
+        appointments.get(self.index)
+    }
+
+    pub fn next(&mut self, appointments: &[Appointment]) {
+        self.index = (self.index + 1) % appointments.len().max(1);
+    }
+
+    pub fn previous(&mut self, appointments: &[Appointment]) {
+        let count = appointments.len().max(1);
+        self.index = (self.index + count - 1) % count;
+    }
+
+    pub fn add(&mut self, appointments: &mut Vec<Appointment>, appointment: Appointment) {
+        if appointments.len() < APPOINTMENTS {
+            self.insert(appointments, appointment);
+        }
+    }
+
+    pub fn remove(&mut self, appointments: &mut Vec<Appointment>) {
+        if self.index < appointments.len() {
+            appointments.remove(self.index);
+        }
+        self.index = self.index.min(appointments.len().saturating_sub(1));
+    }
+
+    // moves the current appointment, which keeps it selected wherever it lands
+    pub fn shift(&mut self, appointments: &mut Vec<Appointment>, minutes: i64) {
+        if self.index >= appointments.len() {
+            return;
+        }
+        let appointment = appointments.remove(self.index);
+        let moved = match appointment.time() {
+            Some(time) => Appointment::new(time + TimeDelta::minutes(minutes), &appointment.text),
+            None => appointment,
+        };
+        self.insert(appointments, moved);
+    }
+
+    fn insert(&mut self, appointments: &mut Vec<Appointment>, appointment: Appointment) {
+        let position = appointments
+            .binary_search(&appointment)
+            .unwrap_or_else(|position| position);
+        appointments.insert(position, appointment);
+        self.index = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, number: &str) -> Record {
+        Record {
+            name: name.to_string(),
+            number: number.to_string(),
+        }
+    }
+
+    // edits the current screen into `name` and `number` and stores it
+    fn save(telememo: &mut Telememo, records: &mut Vec<Record>, name: &str, number: &str) {
+        telememo.edit(records);
+        let typed = Editor::new(Some(&record(name, number)));
+        let editor = telememo.editor.as_mut().unwrap();
+        editor.name = typed.name;
+        editor.number = typed.number;
+        telememo.commit(records);
+    }
+
+    #[test]
+    fn commit_keeps_records_in_order() {
+        let mut records = Vec::new();
+        let mut telememo = Telememo::default();
+        save(&mut telememo, &mut records, "SMITH", "555-0100");
+        telememo.index = records.len();
+        save(&mut telememo, &mut records, "JONES", "555-0199");
+        assert_eq!(telememo.index, 0);
+        telememo.index = records.len();
+        save(&mut telememo, &mut records, "TAXI", "555-0123");
+        assert_eq!(telememo.index, 2);
+        assert_eq!(
+            records,
+            [
+                record("JONES", "555-0199"),
+                record("SMITH", "555-0100"),
+                record("TAXI", "555-0123"),
+            ]
+        );
+
+        // renaming moves the record to its new place
+        telememo.index = 2;
+        save(&mut telememo, &mut records, "CAB", "555-0123");
+        assert_eq!(telememo.index, 0);
+        assert_eq!(records[0], record("CAB", "555-0123"));
+        assert_eq!(records.len(), 3);
+    }
+
+    #[test]
+    fn blanking_a_record_deletes_it() {
+        let mut records = vec![record("JONES", "555-0199"), record("SMITH", "555-0100")];
+        let mut telememo = Telememo {
+            index: 1,
+            editor: None,
+        };
+        save(&mut telememo, &mut records, "", "");
+        assert_eq!(records, [record("JONES", "555-0199")]);
+        assert_eq!(telememo.index, 1);
+    }
+
+    #[test]
+    fn a_duplicate_is_not_stored() {
+        let mut records = vec![record("JONES", "555-0199"), record("SMITH", "555-0100")];
+        let mut telememo = Telememo {
+            index: records.len(),
+            editor: None,
+        };
+        save(&mut telememo, &mut records, "SMITH", "555-0100");
+        assert_eq!(records.len(), 2);
+        assert_eq!(telememo.index, 1);
+    }
+
+    #[test]
+    fn a_full_memory_takes_no_new_entry() {
+        let mut records: Vec<Record> = (0..RECORDS)
+            .map(|index| record(&format!("NAME{:02}", index), "1"))
+            .collect();
+        let mut telememo = Telememo {
+            index: RECORDS,
+            editor: None,
+        };
+        telememo.edit(&records);
+        assert!(telememo.editor.is_none());
+        // existing records can still be changed
+        telememo.previous(&records);
+        telememo.edit(&records);
+        assert!(telememo.editor.is_some());
+        telememo.commit(&mut records);
+        assert_eq!(records.len(), RECORDS);
+    }
+}
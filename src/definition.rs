@@ -0,0 +1,404 @@
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::keymap::{Action, Button, KeyBinding};
+use crate::script;
+use crate::watch::{
+    ALARM_CONTROL, ALARM_SIGNAL_CONTROLS, COUNTDOWN_CONTROLS, Control, DUAL_TIME_CONTROLS, Light,
+    SPLIT_STOPWATCH_CONTROLS, WatchModel,
+};
+
+// fan-made models are json files in here, read once at startup
+pub const DEFINITION_DIR: &str = "watches";
+
+// the built-in renderers need about this much room
+const MIN_LCD_SIZE: (u16, u16) = (30, 10);
+
+static DEFINITIONS: OnceLock<Vec<Definition>> = OnceLock::new();
+
+// a watch described in a file rather than in code: its buttons, mode graph and lcd
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    pub name: String,  // used with --model
+    pub title: String, // printed on the case
+    #[serde(default = "default_light")]
+    pub light: Light,
+    #[serde(default)]
+    pub lcd: Lcd,
+    #[serde(default)]
+    pub keymap: BTreeMap<String, Action>, // keys on top of the usual m, s, r, l and a
+    pub modes: Vec<ModeDefinition>,
+}
+
+fn default_light() -> Light {
+    Light::Led
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Lcd {
+    pub style: LcdStyle,
+    pub width: Option<u16>, // the case, frame included; each style has its own default
+    pub height: Option<u16>,
+    pub label: String,            // printed under the digits of a g-shock style lcd
+    pub rows: Vec<Vec<LcdField>>, // timekeeping laid out top to bottom, the style's own if empty
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LcdStyle {
+    #[default]
+    Classic, // lines of text like the f-91w
+    #[serde(rename = "gshock")]
+    GShock, // big seven-segment digits under a dot-matrix row
+}
+
+// what a row of a laid-out lcd can show, left to right
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LcdField {
+    Time,    // hours, minutes and seconds in the chosen format
+    Digits,  // hours and minutes in big digits, three rows tall and alone on their row
+    Seconds, // the seconds on their own
+    Weekday,
+    Date,
+    Year,
+    Mode,  // the mode's name
+    Label, // the lcd's printed label
+}
+
+impl Lcd {
+    // terminal rows the layout takes, the big digits being three
+    fn rows_height(&self) -> u16 {
+        self.rows
+            .iter()
+            .map(|row| {
+                if row.contains(&LcdField::Digits) {
+                    3
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModeDefinition {
+    pub name: String,
+    pub function: Function,
+    pub next: Option<String>, // where the mode button goes, the following mode by default
+    pub script: Option<PathBuf>, // rhai file for a script mode, next to the definition
+}
+
+// the built-in functions a mode can run
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Function {
+    Timekeeping,
+    Alarm,
+    Chronograph,
+    Timer,
+    WorldTime,
+    Script, // whatever the mode's script does
+}
+
+impl Function {
+    pub fn name(self) -> &'static str {
+        match self {
+            Function::Timekeeping => "timekeeping",
+            Function::Alarm => "alarm",
+            Function::Chronograph => "chronograph",
+            Function::Timer => "timer",
+            Function::WorldTime => "world time",
+            Function::Script => "script",
+        }
+    }
+
+    // what start/stop, reset and alarm do in a mode running the function
+    pub fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            Function::Timekeeping => &[ALARM_CONTROL],
+            Function::Alarm => ALARM_SIGNAL_CONTROLS,
+            Function::Chronograph => SPLIT_STOPWATCH_CONTROLS,
+            Function::Timer => COUNTDOWN_CONTROLS,
+            Function::WorldTime => DUAL_TIME_CONTROLS,
+            Function::Script => &[
+                (Button::StartStop, Control::Script),
+                (Button::Reset, Control::Script),
+                (Button::Alarm, Control::Script),
+            ],
+        }
+    }
+}
+
+impl Lcd {
+    pub fn size(&self) -> (u16, u16) {
+        let (width, height) = match self.style {
+            LcdStyle::Classic => (36, 11),
+            LcdStyle::GShock => (44, 13),
+        };
+        (self.width.unwrap_or(width), self.height.unwrap_or(height))
+    }
+}
+
+impl Definition {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let mut definition: Self = serde_json::from_str(&data)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for mode in &mut definition.modes {
+            mode.script = mode.script.take().map(|script| dir.join(script));
+        }
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn next_mode(&self, index: usize) -> usize {
+        match &self.modes[index].next {
+            Some(name) => self.position(name).unwrap_or(0),
+            None => (index + 1) % self.modes.len(),
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.modes.iter().position(|mode| mode.name == name)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        {
+            bail!(
+                "name '{}' must be lowercase letters and digits only",
+                self.name
+            );
+        }
+        if WatchModel::ALL
+            .iter()
+            .any(|model| model.name() == self.name)
+        {
+            bail!("name '{}' is already a built-in model", self.name);
+        }
+        for key in self.keymap.keys() {
+            KeyBinding::parse(key).with_context(|| format!("bad key '{}' in keymap", key))?;
+        }
+
+        let (width, height) = self.lcd.size();
+        if width < MIN_LCD_SIZE.0 || height < MIN_LCD_SIZE.1 {
+            bail!(
+                "lcd is {}x{}, it needs to be at least {}x{}",
+                width,
+                height,
+                MIN_LCD_SIZE.0,
+                MIN_LCD_SIZE.1
+            );
+        }
+        if self
+            .lcd
+            .rows
+            .iter()
+            .any(|row| row.contains(&LcdField::Digits) && row.len() > 1)
+        {
+            bail!("lcd row with digits can't have anything else on it");
+        }
+        // the top row is kept for the indicators and the bottom one for the hint, inside the frame
+        if self.lcd.rows_height() + 4 > height {
+            bail!(
+                "lcd rows take {} lines, an lcd {} high only has room for {}",
+                self.lcd.rows_height(),
+                height,
+                height - 4
+            );
+        }
+
+        let Some(first) = self.modes.first() else {
+            bail!("no modes defined");
+        };
+        for (index, mode) in self.modes.iter().enumerate() {
+            if self.modes[..index]
+                .iter()
+                .any(|other| other.name == mode.name)
+            {
+                bail!("mode '{}' is defined twice", mode.name);
+            }
+            if let Some(next) = &mode.next
+                && self.position(next).is_none()
+            {
+                bail!("mode '{}' goes to unknown mode '{}'", mode.name, next);
+            }
+            match (&mode.script, mode.function) {
+                (Some(path), Function::Script) => script::compile(path)
+                    .with_context(|| format!("script for mode '{}'", mode.name))?,
+                (None, Function::Script) => bail!("script mode '{}' has no script", mode.name),
+                (Some(_), _) => bail!("mode '{}' has a script but isn't a script mode", mode.name),
+                (None, _) => {}
+            }
+        }
+
+        // pressing mode from the first mode has to visit every mode and come back round
+        let mut visited = vec![false; self.modes.len()];
+        let mut index = 0;
+        while !visited[index] {
+            visited[index] = true;
+            index = self.next_mode(index);
+        }
+        if let Some(mode) = self
+            .modes
+            .iter()
+            .zip(&visited)
+            .find_map(|(mode, visited)| (!visited).then_some(mode))
+        {
+            bail!(
+                "mode '{}' can't be reached with the mode button from '{}'",
+                mode.name,
+                first.name
+            );
+        }
+        if index != 0 {
+            bail!(
+                "the mode button goes round from '{}' without coming back to '{}'",
+                self.modes[index].name,
+                first.name
+            );
+        }
+        Ok(())
+    }
+}
+
+// reads every definition in the directory; one bad file stops startup with its name in the error
+pub fn load(dir: &Path) -> Result<()> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+
+    let mut definitions: Vec<Definition> = Vec::new();
+    for path in paths {
+        let definition = Definition::from_file(&path)
+            .with_context(|| format!("invalid watch definition {}", path.display()))?;
+        if definitions
+            .iter()
+            .any(|other| other.name == definition.name)
+        {
+            bail!(
+                "watch definition {} reuses the name '{}'",
+                path.display(),
+                definition.name
+            );
+        }
+        definitions.push(definition);
+    }
+    // only the first load counts, later ones would leave models pointing at the wrong files
+    let _ = DEFINITIONS.set(definitions);
+    Ok(())
+}
+
+pub fn loaded() -> &'static [Definition] {
+    DEFINITIONS.get().map_or(&[], Vec::as_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a definition with the given modes and lcd rows, checked the way a file would be
+    fn validate(modes: &str, rows: &str) -> Result<()> {
+        let json = format!(
+            r#"{{"name": "test", "title": "Test", "lcd": {{"rows": {}}}, "modes": {}}}"#,
+            rows, modes
+        );
+        serde_json::from_str::<Definition>(&json)
+            .unwrap()
+            .validate()
+    }
+
+    fn error(modes: &str, rows: &str) -> String {
+        format!("{:#}", validate(modes, rows).unwrap_err())
+    }
+
+    #[test]
+    fn mode_graph_going_round_is_accepted() {
+        let modes = r#"[
+            {"name": "time", "function": "timekeeping"},
+            {"name": "alarm", "function": "alarm", "next": "chrono"},
+            {"name": "timer", "function": "timer", "next": "time"},
+            {"name": "chrono", "function": "chronograph", "next": "timer"}
+        ]"#;
+        validate(modes, r#"[["weekday", "date"], ["digits"], ["seconds"]]"#).unwrap();
+    }
+
+    #[test]
+    fn duplicate_mode_is_rejected() {
+        let modes = r#"[
+            {"name": "time", "function": "timekeeping"},
+            {"name": "time", "function": "alarm"}
+        ]"#;
+        assert!(error(modes, "[]").contains("'time' is defined twice"));
+    }
+
+    #[test]
+    fn unknown_next_is_rejected() {
+        let modes = r#"[
+            {"name": "time", "function": "timekeeping", "next": "stopwatch"},
+            {"name": "alarm", "function": "alarm"}
+        ]"#;
+        assert!(error(modes, "[]").contains("unknown mode 'stopwatch'"));
+    }
+
+    #[test]
+    fn unreachable_mode_is_rejected() {
+        let modes = r#"[
+            {"name": "time", "function": "timekeeping", "next": "time"},
+            {"name": "alarm", "function": "alarm"}
+        ]"#;
+        assert!(error(modes, "[]").contains("'alarm' can't be reached"));
+    }
+
+    #[test]
+    fn cycle_that_doesnt_come_back_is_rejected() {
+        // time goes to alarm, but alarm and timer only go round each other
+        let modes = r#"[
+            {"name": "time", "function": "timekeeping"},
+            {"name": "alarm", "function": "alarm"},
+            {"name": "timer", "function": "timer", "next": "alarm"}
+        ]"#;
+        let message = error(modes, "[]");
+        assert!(
+            message.contains("without coming back to 'time'"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rows_taller_than_the_lcd_are_rejected() {
+        let modes = r#"[{"name": "time", "function": "timekeeping"}]"#;
+        // the classic lcd is 11 high, leaving 7 lines for rows
+        validate(
+            modes,
+            r#"[["digits"], ["date"], ["time"], ["mode"], ["label"]]"#,
+        )
+        .unwrap();
+        let message = error(
+            modes,
+            r#"[["digits"], ["date"], ["time"], ["mode"], ["label"], ["year"]]"#,
+        );
+        assert!(message.contains("rows take 8 lines"), "{}", message);
+        assert!(error(modes, r#"[["digits", "date"]]"#).contains("can't have anything else"));
+    }
+}
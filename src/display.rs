@@ -1,44 +1,193 @@
-use chrono::Timelike;
+use chrono::{NaiveTime, TimeDelta, Timelike};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{
+        Block, BorderType, Borders, Clear, Paragraph, Wrap,
+        canvas::{Canvas, Circle, Line as CanvasLine, Points},
+    },
 };
 
-use crate::watch::{F91WMode, Watch, WatchMode, WatchModel};
-
-pub fn ui(f: &mut Frame, watch: &Watch) {
-    let size = f.area();
+use crate::calculator::CalcKey;
+use crate::databank::{self, Field};
+use crate::definition::{self, Definition, Function, LcdField, LcdStyle};
+use crate::gallery::Gallery;
+use crate::game;
+use crate::keymap::{Action, AppAction, Button};
+use crate::manual::Manual;
+use crate::mcu;
+use crate::menu::SettingsMenu;
+use crate::script::Segment;
+use crate::sensors;
+use crate::tide;
+use crate::touch::{self, TouchKey};
+use crate::watch::{
+    AQ230Mode, CA53WMode, CMD40Mode, DB36Mode, DW5600Mode, F91WMode, GD8Mode, GLX5600Mode,
+    GWM5610Mode, Light, Module, PRG240Mode, VDB1000Mode, W800HMode, Watch, WatchMode, WatchModel,
+};
 
+// where the watch sits in the terminal, frame included
+pub fn watch_area(size: Rect, model: WatchModel) -> Rect {
     // different sizes for different watches
-    let (watch_width, watch_height) = match watch.model {
+    let (watch_width, watch_height) = match model {
         WatchModel::AE1200 => (50, 15),
         WatchModel::F91W => (30, 10),
+        WatchModel::F105W => (32, 10),
+        WatchModel::A158W | WatchModel::A168W => (34, 11),
+        WatchModel::CA53W => (36, 12),
+        WatchModel::DW5600E | WatchModel::GWM5610 => (44, 13),
+        WatchModel::DB36 => (36, 11),
+        WatchModel::AQ230 => (56, 13),
+        WatchModel::PRG240 => (44, 13),
+        WatchModel::W800H => (40, 11),
+        WatchModel::MQ24 => (48, 24),
+        WatchModel::GLX5600 => (44, 13),
+        WatchModel::CMD40 => (40, 11),
+        WatchModel::VDB1000 => (40, 17),
+        WatchModel::GD8 => (36, 15),
+        WatchModel::Firmware => (40, 11),
+        WatchModel::Custom(index) => definition::loaded()[index].lcd.size(),
     };
 
-    let watch_area = Rect {
-        x: (size.width.saturating_sub(watch_width)) / 2,
-        y: (size.height.saturating_sub(watch_height)) / 2,
+    Rect {
+        x: size.x + (size.width.saturating_sub(watch_width)) / 2,
+        y: size.y + (size.height.saturating_sub(watch_height)) / 2,
         width: watch_width.min(size.width),
         height: watch_height.min(size.height),
-    };
+    }
+}
+
+// the lcd inside the frame, which is what touch input is measured against
+pub fn lcd_area(size: Rect, model: WatchModel) -> Rect {
+    watch_area(size, model).inner(Margin::new(1, 1))
+}
+
+// the terminal split into a grid with a cell for each watch, filled a row at a time
+pub fn dashboard_cells(size: Rect, count: usize) -> Vec<Rect> {
+    let columns = (1..)
+        .find(|columns| columns * columns >= count)
+        .unwrap_or(1);
+    let rows = count.div_ceil(columns);
+    Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows])
+        .split(size)
+        .iter()
+        .flat_map(|row| {
+            Layout::horizontal(vec![Constraint::Ratio(1, columns as u32); columns])
+                .split(*row)
+                .to_vec()
+        })
+        .take(count)
+        .collect()
+}
+
+pub fn ui(f: &mut Frame, watches: &[Watch], focus: usize, menu: &SettingsMenu, help: bool) {
+    let size = f.area();
+    for (index, (watch, cell)) in watches
+        .iter()
+        .zip(dashboard_cells(size, watches.len()))
+        .enumerate()
+    {
+        // focus only needs showing when there's more than one watch to choose from
+        render_watch(f, cell, watch, watches.len() > 1 && index == focus);
+    }
+
+    if help {
+        render_manual(f, size, &watches[focus]);
+    }
+    if menu.open {
+        render_settings_menu(f, size, &watches[focus], menu);
+    }
+}
+
+// the model list on the left, and the highlighted watch running on the right above what it does
+pub fn gallery_ui(f: &mut Frame, watches: &[Watch], gallery: &Gallery) {
+    let size = f.area();
+    let watch = &watches[gallery.selected];
+    let [list_area, detail_area] =
+        Layout::horizontal([Constraint::Length(34), Constraint::Min(0)]).areas(size);
+
+    let list_block = Block::default()
+        .title("Choose a watch")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(watch.theme.label));
+    // keep the highlighted model in view once the list outgrows the terminal
+    let rows = list_block.inner(list_area).height as usize;
+    let first = (gallery.selected + 1).saturating_sub(rows);
+    let lines: Vec<Line> = watches
+        .iter()
+        .enumerate()
+        .skip(first)
+        .map(|(index, other)| {
+            let style = if index == gallery.selected {
+                Style::default()
+                    .fg(watch.theme.date)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(watch.theme.date)
+            };
+            Line::from(Span::styled(
+                format!(" {:<9}{:<22}", other.model.name(), other.model.title()),
+                style,
+            ))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).block(list_block), list_area);
+
+    let mut info = vec![Line::from(watch.model.description()), Line::from("")];
+    info.extend(
+        watch
+            .model
+            .features()
+            .into_iter()
+            .map(|feature| Line::from(format!("• {}", feature))),
+    );
+    info.push(Line::from(""));
+    info.push(Line::from("arrows choose, enter start, esc quit"));
+    let info_height = info.len() as u16;
+    let [preview_area, info_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(info_height)])
+            .areas(detail_area.inner(Margin::new(1, 0)));
+
+    render_watch(f, preview_area, watch, false);
+    f.render_widget(
+        Paragraph::new(info).style(Style::default().fg(watch.theme.date)),
+        info_area,
+    );
+}
+
+// one watch centred in its part of the terminal
+fn render_watch(f: &mut Frame, size: Rect, watch: &Watch, focused: bool) {
+    let watch_area = watch_area(size, watch.model);
 
     // draw the frame with model-specific title
-    let title = match watch.model {
-        WatchModel::AE1200 => "Casio AE-1200",
-        WatchModel::F91W => "Casio F-91W",
-    };
+    let title = watch.model.title();
 
-    let watch_block = Block::default()
+    let mut watch_block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue));
+        .border_style(Style::default().fg(watch.theme.case));
+    // a double frame around the watch that gets the keys
+    if focused {
+        watch_block = watch_block.border_type(BorderType::Double).border_style(
+            Style::default()
+                .fg(watch.theme.case)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
 
     f.render_widget(Clear, watch_area);
     let watch_inner = watch_block.inner(watch_area);
     f.render_widget(watch_block, watch_area);
+    f.render_widget(
+        Block::default().style(Style::default().bg(watch.theme.lcd)),
+        watch_inner,
+    );
 
-    match watch.model {
-        WatchModel::AE1200 => match watch.mode {
+    if watch.light_on {
+        render_light(f, watch_inner, watch);
+    }
+
+    match watch.model.module() {
+        Module::AE1200 => match watch.mode {
             WatchMode::Home => {
                 render_time_display(f, watch_inner, watch);
             }
@@ -55,10 +204,18 @@ pub fn ui(f: &mut Frame, watch: &Watch) {
                 render_stopwatch_display(f, watch_inner, watch);
             }
         },
-        WatchModel::F91W => match watch.f91w_mode {
-            F91WMode::Time => {
-                render_f91w_time_display(f, watch_inner, watch);
-            }
+        Module::F91W => match watch.f91w_mode {
+            F91WMode::Time => match watch.model {
+                WatchModel::A158W | WatchModel::A168W => {
+                    render_a158w_time_display(f, watch_inner, watch);
+                }
+                WatchModel::F105W => {
+                    render_f105w_time_display(f, watch_inner, watch);
+                }
+                _ => {
+                    render_f91w_time_display(f, watch_inner, watch);
+                }
+            },
             F91WMode::Alarm => {
                 render_f91w_alarm_display(f, watch_inner, watch);
             }
@@ -66,9 +223,374 @@ pub fn ui(f: &mut Frame, watch: &Watch) {
                 render_f91w_stopwatch_display(f, watch_inner, watch);
             }
         },
+        Module::CA53W => match watch.ca53w_mode {
+            CA53WMode::Time => {
+                render_ca53w_time_display(f, watch_inner, watch);
+            }
+            CA53WMode::Calculator => {
+                render_calculator_display(f, watch_inner, watch);
+            }
+            CA53WMode::Alarm => {
+                render_f91w_alarm_display(f, watch_inner, watch);
+            }
+            CA53WMode::Stopwatch => {
+                render_f91w_stopwatch_display(f, watch_inner, watch);
+            }
+            CA53WMode::DualTime => {
+                render_dual_time_display(f, watch_inner, watch);
+            }
+        },
+        Module::DW5600 => match watch.dw5600_mode {
+            DW5600Mode::Time => {
+                render_dw5600_time_display(f, watch_inner, watch);
+            }
+            DW5600Mode::Stopwatch => {
+                render_dw5600_stopwatch_display(f, watch_inner, watch);
+            }
+            DW5600Mode::Timer => {
+                render_dw5600_timer_display(f, watch_inner, watch);
+            }
+            DW5600Mode::Alarm => {
+                render_dw5600_alarm_display(f, watch_inner, watch);
+            }
+        },
+        Module::GWM5610 => match watch.gwm5610_mode {
+            GWM5610Mode::Time => {
+                render_dw5600_time_display(f, watch_inner, watch);
+            }
+            GWM5610Mode::Receive => {
+                render_gwm5610_receive_display(f, watch_inner, watch);
+            }
+            GWM5610Mode::Stopwatch => {
+                render_dw5600_stopwatch_display(f, watch_inner, watch);
+            }
+            GWM5610Mode::Timer => {
+                render_dw5600_timer_display(f, watch_inner, watch);
+            }
+            GWM5610Mode::Alarm => {
+                render_dw5600_alarm_display(f, watch_inner, watch);
+            }
+        },
+        Module::DB36 => match watch.db36_mode {
+            DB36Mode::Time => {
+                render_f91w_time_display(f, watch_inner, watch);
+            }
+            DB36Mode::Telememo => {
+                render_telememo_display(f, watch_inner, watch);
+            }
+            DB36Mode::Alarm => {
+                render_f91w_alarm_display(f, watch_inner, watch);
+            }
+            DB36Mode::Stopwatch => {
+                render_f91w_stopwatch_display(f, watch_inner, watch);
+            }
+        },
+        Module::AQ230 => {
+            render_aq230_display(f, watch_inner, watch);
+        }
+        Module::PRG240 => {
+            render_prg240_display(f, watch_inner, watch);
+        }
+        Module::MQ24 => {
+            render_mq24_display(f, watch_inner, watch);
+        }
+        Module::Custom => {
+            render_custom_display(f, watch_inner, watch);
+        }
+        Module::GD8 => match watch.gd8_mode {
+            GD8Mode::Time => {
+                render_f91w_time_display(f, watch_inner, watch);
+            }
+            GD8Mode::Game => {
+                render_game_display(f, watch_inner, watch);
+            }
+            GD8Mode::Alarm => {
+                render_f91w_alarm_display(f, watch_inner, watch);
+            }
+        },
+        Module::VDB1000 => match watch.vdb1000_mode {
+            VDB1000Mode::Time => {
+                render_f91w_time_display(f, watch_inner, watch);
+            }
+            VDB1000Mode::Calculator => {
+                render_touch_calculator_display(f, watch_inner, watch);
+            }
+            VDB1000Mode::Memo => {
+                render_memo_display(f, watch_inner, watch);
+            }
+            VDB1000Mode::Schedule => {
+                render_schedule_display(f, watch_inner, watch);
+            }
+        },
+        Module::CMD40 => match watch.cmd40_mode {
+            CMD40Mode::Time => {
+                render_f91w_time_display(f, watch_inner, watch);
+            }
+            CMD40Mode::Remote => {
+                render_remote_display(f, watch_inner, watch);
+            }
+            CMD40Mode::Learn => {
+                render_learn_display(f, watch_inner, watch);
+            }
+            CMD40Mode::Alarm => {
+                render_f91w_alarm_display(f, watch_inner, watch);
+            }
+        },
+        Module::GLX5600 => match watch.glx5600_mode {
+            GLX5600Mode::Time => {
+                render_dw5600_time_display(f, watch_inner, watch);
+            }
+            GLX5600Mode::Tide => {
+                render_tide_display(f, watch_inner, watch);
+            }
+            GLX5600Mode::Moon => {
+                render_moon_display(f, watch_inner, watch);
+            }
+            GLX5600Mode::Stopwatch => {
+                render_dw5600_stopwatch_display(f, watch_inner, watch);
+            }
+            GLX5600Mode::Alarm => {
+                render_dw5600_alarm_display(f, watch_inner, watch);
+            }
+        },
+        Module::Firmware => {
+            render_firmware_display(f, watch_inner, watch);
+        }
+        Module::W800H => match watch.w800h_mode {
+            W800HMode::Time => {
+                render_f91w_time_display(f, watch_inner, watch);
+            }
+            W800HMode::DualTime => {
+                render_dual_time_display(f, watch_inner, watch);
+            }
+            W800HMode::Stopwatch => {
+                render_f91w_stopwatch_display(f, watch_inner, watch);
+            }
+            W800HMode::Timer => {
+                render_countdown_display(f, watch_inner, watch);
+            }
+            W800HMode::Alarm => {
+                render_multi_alarm_display(f, watch_inner, watch);
+            }
+        },
     }
 
     render_status_indicators(f, watch_area, watch);
+    render_script_status(f, size, watch_area, watch);
+}
+
+// drawn under the lcd contents so the segments stay on top of the glow
+fn render_light(f: &mut Frame, area: Rect, watch: &Watch) {
+    let glow = if watch.theme.backlight == Color::Reset {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().bg(watch.theme.backlight)
+    };
+
+    match watch.model.light() {
+        // the led sits at the left edge and only lights that side of the lcd
+        Light::Led => {
+            let led_area = Rect {
+                width: 2.min(area.width),
+                ..area
+            };
+            f.render_widget(Block::default().style(glow), led_area);
+        }
+        // an el panel lights the whole lcd evenly
+        Light::El => {
+            f.render_widget(Block::default().style(glow), area);
+        }
+        Light::None => {}
+    }
+}
+
+fn render_settings_menu(f: &mut Frame, size: Rect, watch: &Watch, menu: &SettingsMenu) {
+    let menu_width = 40;
+    let menu_height = menu.fields.len() as u16 + 5;
+
+    let menu_area = Rect {
+        x: (size.width.saturating_sub(menu_width)) / 2,
+        y: (size.height.saturating_sub(menu_height)) / 2,
+        width: menu_width.min(size.width),
+        height: menu_height.min(size.height),
+    };
+
+    let mut lines = vec![Line::from("")];
+    for (index, field) in menu.fields.iter().enumerate() {
+        let selected = index == menu.selected;
+        let value = match &menu.editor {
+            Some(editor) if selected => editor.display(*field),
+            _ => field.value(&watch.settings),
+        };
+
+        let style = if selected {
+            Style::default()
+                .fg(watch.theme.date)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(watch.theme.date)
+        };
+
+        lines.push(Line::from(vec![Span::styled(
+            format!(" {:<20}{:>16} ", field.label(), value),
+            style,
+        )]));
+    }
+    lines.push(Line::from(""));
+    lines.push(match &menu.error {
+        Some(error) => Line::styled(error.clone(), Style::default().fg(watch.theme.alarm)),
+        None => Line::from(if menu.editor.is_some() {
+            "arrows adjust, enter save, esc cancel".to_string()
+        } else {
+            watch
+                .keymap
+                .key_for(&Action::App(AppAction::Settings))
+                .map_or_else(
+                    || "arrows move, enter edit, esc close".to_string(),
+                    |key| format!("arrows move, enter edit, '{}'/esc close", key),
+                )
+        }),
+    });
+
+    let menu_block = Block::default()
+        .title("Settings")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(watch.theme.label));
+
+    f.render_widget(Clear, menu_area);
+    f.render_widget(Paragraph::new(lines).block(menu_block), menu_area);
+}
+
+// the case with its keys, the mode flow and what each button does right now; the watch keeps
+// taking button presses underneath so the manual follows along
+fn render_manual(f: &mut Frame, size: Rect, watch: &Watch) {
+    let manual = Manual::new(watch);
+    let label = Style::default().fg(watch.theme.label);
+    let text = Style::default().fg(watch.theme.date);
+    let key = |button: Button| {
+        watch
+            .keymap
+            .key_for(&Action::Press(button))
+            .map_or_else(|| "-".to_string(), |key| key.to_string())
+    };
+
+    // light and mode down the left of the case, the rest down the right
+    let crown = matches!(
+        watch.model.module(),
+        Module::AQ230 | Module::MQ24 | Module::Firmware
+    );
+    let mut right = vec![Button::Alarm, Button::StartStop];
+    if crown {
+        right.push(Button::CrownPull);
+    }
+    right.push(Button::Reset);
+    let name = watch.model.name().to_uppercase();
+    let case_width = name.len() + 6;
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(" Buttons", label)),
+        Line::from(format!("{:>10}┌{}┐", "", "─".repeat(case_width))),
+    ];
+    for (row, button) in right.iter().enumerate() {
+        let left = match row {
+            0 if watch.model.light() != Light::None => Some(Button::Light),
+            _ if row == right.len() - 1 => Some(Button::Mode),
+            _ => None,
+        };
+        let left = match left {
+            Some(button) => format!("{:>6} ───┤", key(button)),
+            None => format!("{:>10}│", ""),
+        };
+        let middle = if row == (right.len() - 1) / 2 {
+            &name
+        } else {
+            ""
+        };
+        let right = match button {
+            Button::CrownPull => format!(
+                "╞═◉ {} pull, {} {} turn",
+                key(Button::CrownPull),
+                key(Button::CrownBack),
+                key(Button::CrownForward)
+            ),
+            button => format!("├─── {}", key(*button)),
+        };
+        lines.push(Line::from(format!(
+            "{}{:^width$}{}",
+            left,
+            middle,
+            right,
+            width = case_width
+        )));
+    }
+    lines.push(Line::from(format!(
+        "{:>10}└{}┘",
+        "",
+        "─".repeat(case_width)
+    )));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(" Modes", label)));
+    let mut flow = vec![Span::raw(" ")];
+    for (index, mode) in manual.modes.iter().enumerate() {
+        let style = if manual.current == Some(index) {
+            text.add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            text
+        };
+        flow.push(Span::styled(*mode, style));
+        flow.push(Span::raw(" → "));
+    }
+    if manual.modes.is_empty() {
+        flow.push(Span::styled("none", text));
+    } else {
+        flow.pop();
+        flow.push(Span::raw(" ↺"));
+    }
+    lines.push(Line::from(flow));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(" In this mode", label)));
+    for (button, role) in &manual.controls {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>6}  ", key(*button)), label),
+            Span::styled(
+                format!("{:<14}{}", button.name().replace('_', " "), role),
+                text,
+            ),
+        ]));
+    }
+    if let Some(note) = manual.note {
+        lines.push(Line::from(Span::styled(format!(" {}", note), text)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        watch
+            .keymap
+            .key_for(&Action::App(AppAction::Help))
+            .map_or_else(String::new, |key| format!(" '{}' or esc closes", key)),
+    ));
+
+    let manual_width = 56;
+    let manual_height = lines.len() as u16 + 2;
+    let manual_area = Rect {
+        x: (size.width.saturating_sub(manual_width)) / 2,
+        y: (size.height.saturating_sub(manual_height)) / 2,
+        width: manual_width.min(size.width),
+        height: manual_height.min(size.height),
+    };
+    let manual_block = Block::default()
+        .title(format!("{} manual", watch.model.title()))
+        .borders(Borders::ALL)
+        .border_style(label);
+
+    f.render_widget(Clear, manual_area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(manual_block)
+            .wrap(Wrap { trim: false }),
+        manual_area,
+    );
 }
 
 fn render_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
@@ -85,19 +607,19 @@ fn render_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(vec![Span::styled(
             format!("{} {}", day_text, date_text),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(watch.theme.date),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(""),
-        Line::from("press 'M' for mode, 'L' for backlight"),
+        Line::from(watch.hint(&[Button::Mode, Button::Light])),
     ])
     .block(Block::default());
 
@@ -107,8 +629,6 @@ fn render_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
 }
 
 fn render_analog_display(f: &mut Frame, area: Rect, watch: &Watch) {
-    use std::f64::consts::PI;
-
     // create a small area for the analog clock in the top-left corner
     let analog_area = Rect {
         x: area.x + 2,
@@ -117,118 +637,62 @@ fn render_analog_display(f: &mut Frame, area: Rect, watch: &Watch) {
         height: 5.min(area.height),
     };
 
-    let time = &watch.time_manager.current_time;
+    render_dial(
+        f,
+        analog_area,
+        watch,
+        watch.time_manager.current_time.time(),
+        true,
+    );
+}
+
+// a round dial with hands drawn in braille dots, filling the area
+fn render_dial(f: &mut Frame, area: Rect, watch: &Watch, time: NaiveTime, seconds_hand: bool) {
+    use std::f64::consts::PI;
+
     let hour = time.hour() as f64;
     let minute = time.minute() as f64;
     let second = time.second() as f64;
 
-    // hour hand: 360 degrees / 12 hours = 30 degrees per hour + minute adjustment
-    let hour_angle = (hour % 12.0) * 30.0 + minute * 0.5;
-    let _hour_angle_rad = hour_angle * PI / 180.0;
+    // angles run clockwise from twelve o'clock
+    let hour_angle = (hour % 12.0 + minute / 60.0) * PI / 6.0;
+    let minute_angle = (minute + second / 60.0) * PI / 30.0;
+    let second_angle = second * PI / 30.0;
 
-    // minute hand: 360 degrees / 60 minutes = 6 degrees per minute + second adjustment
-    let minute_angle = minute * 6.0 + second * 0.1;
-    let _minute_angle_rad = minute_angle * PI / 180.0;
-
-    // second hand: 360 degrees / 60 seconds = 6 degrees per second
-    let second_angle = second * 6.0;
-    let _second_angle_rad = second_angle * PI / 180.0;
+    let color = watch.theme.hands;
+    let hand = |angle: f64, from: f64, to: f64| {
+        CanvasLine::new(
+            from * angle.sin(),
+            from * angle.cos(),
+            to * angle.sin(),
+            to * angle.cos(),
+            color,
+        )
+    };
 
-    let analog_display = Paragraph::new(vec![
-        Line::from(format!("┌────────┐")),
-        Line::from(format!(
-            "│  {}{}  │",
-            if hour_angle >= 270.0 || hour_angle <= 90.0 {
-                "▲"
-            } else {
-                " "
-            },
-            if minute_angle >= 270.0 || minute_angle <= 90.0 {
-                "●"
-            } else {
-                " "
-            }
-        )),
-        Line::from(format!(
-            "│{}{} {}{}{}│",
-            if hour_angle > 180.0 && hour_angle <= 360.0 {
-                "◀"
-            } else {
-                " "
-            },
-            if minute_angle > 180.0 && minute_angle <= 360.0 {
-                "●"
-            } else {
-                " "
-            },
-            if second_angle > 180.0 && second_angle <= 360.0 {
-                "·"
-            } else {
-                " "
-            },
-            if minute_angle > 0.0 && minute_angle <= 180.0 {
-                "●"
-            } else {
-                " "
-            },
-            if hour_angle > 0.0 && hour_angle <= 180.0 {
-                "▶"
-            } else {
-                " "
+    let dial = Canvas::default()
+        .marker(symbols::Marker::Braille)
+        .background_color(watch.theme.lcd)
+        .x_bounds([-1.0, 1.0])
+        .y_bounds([-1.0, 1.0])
+        .paint(|ctx| {
+            ctx.draw(&Circle {
+                x: 0.0,
+                y: 0.0,
+                radius: 0.98,
+                color,
+            });
+            for index in 0..12 {
+                ctx.draw(&hand(index as f64 * PI / 6.0, 0.8, 0.98));
             }
-        )),
-        Line::from(format!(
-            "│  {}{}  │",
-            if minute_angle > 90.0 && minute_angle <= 270.0 {
-                "●"
-            } else {
-                " "
-            },
-            if hour_angle > 90.0 && hour_angle <= 270.0 {
-                "▼"
-            } else {
-                " "
+            ctx.draw(&hand(hour_angle, 0.0, 0.5));
+            ctx.draw(&hand(minute_angle, 0.0, 0.8));
+            if seconds_hand {
+                ctx.draw(&hand(second_angle, 0.0, 0.9));
             }
-        )),
-        Line::from(format!("└────────┘")),
-    ])
-    .style(Style::default().fg(Color::Yellow));
-
-    f.render_widget(analog_display, analog_area);
-}
-
-fn render_date_display(f: &mut Frame, area: Rect, watch: &Watch) {
-    let date_text = watch
-        .time_manager
-        .format_date(watch.settings.date_format_us);
-    let day_text = watch.time_manager.format_day_of_week();
-    let year_text = watch.time_manager.current_time.format("%Y").to_string();
-
-    let date_display = Paragraph::new(vec![
-        Line::from(""),
-        Line::from(""),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            format!("{} {}", day_text, date_text),
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )])
-        .alignment(Alignment::Center),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            year_text,
-            Style::default().fg(Color::Cyan),
-        )])
-        .alignment(Alignment::Center),
-        Line::from(""),
-        Line::from(""),
-        Line::from(""),
-        Line::from("Press 'M' for mode, 'L' for light"),
-    ])
-    .block(Block::default());
+        });
 
-    f.render_widget(date_display, area);
+    f.render_widget(dial, area);
 }
 
 fn render_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
@@ -245,7 +709,7 @@ fn render_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             "STOPWATCH",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(watch.theme.label)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -253,7 +717,7 @@ fn render_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -261,29 +725,39 @@ fn render_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             status,
             if watch.stopwatch_running {
-                Style::default().fg(Color::Red)
+                Style::default().fg(watch.theme.running)
             } else {
-                Style::default().fg(Color::Blue)
+                Style::default().fg(watch.theme.stopped)
             },
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(""),
-        Line::from("press 'S' start/stop, 'R' reset"),
-        Line::from("press 'M' for mode, 'L' for backlight"),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Mode, Button::Light])),
     ])
     .block(Block::default());
 
     f.render_widget(stopwatch_display, area);
 }
 
+// hundredths for the first hour, then H:MM'SS up to the 24 hours a stopwatch like the w-800h runs to
 fn format_stopwatch_time(milliseconds: u64) -> String {
     let total_seconds = milliseconds / 1000;
     let minutes = total_seconds / 60;
     let seconds = total_seconds % 60;
     let millis = (milliseconds % 1000) / 10;
 
-    format!("{:02}:{:02}.{:02}", minutes, seconds, millis)
+    if total_seconds < 3600 {
+        format!("{:02}:{:02}.{:02}", minutes, seconds, millis)
+    } else {
+        format!(
+            "{}:{:02}'{:02}",
+            total_seconds / 3600,
+            minutes % 60,
+            seconds
+        )
+    }
 }
 
 fn render_status_indicators(f: &mut Frame, area: Rect, watch: &Watch) {
@@ -291,7 +765,7 @@ fn render_status_indicators(f: &mut Frame, area: Rect, watch: &Watch) {
         let light_indicator = Paragraph::new("LGT")
             .style(
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(watch.theme.light)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Right);
@@ -306,9 +780,31 @@ fn render_status_indicators(f: &mut Frame, area: Rect, watch: &Watch) {
         f.render_widget(light_indicator, light_area);
     }
 
+    // an analog-only watch has no buzzer, so no alarm or signal to show, and firmware has its own
+    if matches!(watch.model.module(), Module::MQ24 | Module::Firmware) {
+        return;
+    }
+
+    // the alarm indicator flashes while the alarm or the hourly signal is sounding
+    let flash_off = (watch.alarm_ringing()
+        || watch.signal_start_time.is_some()
+        || watch.countdown_beep_start_time.is_some())
+        && watch.time_manager.current_time.timestamp_subsec_millis() >= 500;
+    let mut indicators = Vec::new();
     if watch.settings.alarm_enabled {
-        let alarm_indicator = Paragraph::new("ALM")
-            .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        indicators.push("ALM");
+    }
+    if watch.settings.hourly_signal {
+        indicators.push("SIG");
+    }
+
+    if !indicators.is_empty() && !flash_off {
+        let alarm_indicator = Paragraph::new(indicators.join(" "))
+            .style(
+                Style::default()
+                    .fg(watch.theme.alarm)
+                    .add_modifier(Modifier::BOLD),
+            )
             .alignment(Alignment::Left);
 
         let alarm_area = Rect {
@@ -333,7 +829,7 @@ fn render_world_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             "WT",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(watch.theme.label)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -341,13 +837,13 @@ fn render_world_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(""),
-        Line::from("Press 'M' for mode, 'L' for backlight"),
+        Line::from(watch.hint(&[Button::Mode, Button::Light])),
     ])
     .block(Block::default());
 
@@ -370,18 +866,20 @@ fn render_alarm_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(""),
         Line::from(vec![Span::styled(
             "ALM",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(watch.theme.alarm)
+                .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(vec![Span::styled(
             alarm_status,
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(watch.theme.date),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(""),
-        Line::from("Press 'A' to toggle, 'M' for mode"),
+        Line::from(watch.hint(&[Button::Alarm, Button::Mode])),
     ])
     .block(Block::default());
 
@@ -402,7 +900,7 @@ fn render_timer_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             "TMR",
             Style::default()
-                .fg(Color::Magenta)
+                .fg(watch.theme.label)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -410,7 +908,7 @@ fn render_timer_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -418,16 +916,16 @@ fn render_timer_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             status,
             if watch.timer_running {
-                Style::default().fg(Color::Red)
+                Style::default().fg(watch.theme.running)
             } else {
-                Style::default().fg(Color::Blue)
+                Style::default().fg(watch.theme.stopped)
             },
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(""),
-        Line::from("Press 'S' start/stop, 'R' reset"),
-        Line::from("Press 'M' for mode, 'L' for backlight"),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Mode, Button::Light])),
     ])
     .block(Block::default());
 
@@ -456,70 +954,168 @@ fn render_f91w_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(vec![Span::styled(
             date_text,
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(watch.theme.date),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
-        Line::from("press 'M' for mode"),
+        Line::from(watch.hint(&[Button::Mode])),
     ])
     .block(Block::default());
 
     f.render_widget(time_display, area);
 }
 
-fn render_f91w_alarm_display(f: &mut Frame, area: Rect, watch: &Watch) {
-    let alarm_status = if watch.settings.alarm_enabled {
+// the f-91w module behind an el panel, with the illuminator print above the lcd
+fn render_f105w_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let time_text = watch
+        .time_manager
+        .format_time(watch.settings.time_format_24h);
+    let date_text = format!(
+        "{} {}",
+        watch.time_manager.format_day_of_week().to_uppercase(),
         watch
-            .settings
-            .alarm_time
-            .clone()
-            .unwrap_or_else(|| "--:--".to_string())
-    } else {
-        "OFF".to_string()
-    };
+            .time_manager
+            .format_date(watch.settings.date_format_us)
+    );
 
-    let alarm_display = Paragraph::new(vec![
+    let time_display = Paragraph::new(vec![
         Line::from(""),
         Line::from(vec![Span::styled(
-            "ALARM",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            "ILLUMINATOR",
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
         Line::from(vec![Span::styled(
-            alarm_status,
-            Style::default().fg(Color::Cyan),
+            time_text,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            date_text,
+            Style::default().fg(watch.theme.date),
         )])
         .alignment(Alignment::Center),
         Line::from(""),
-        Line::from("press 'A' toggle, 'M' mode"),
+        Line::from(vec![Span::styled(
+            "ALARM CHRONOGRAPH",
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(watch.hint(&[Button::Mode, Button::Light])),
     ])
     .block(Block::default());
 
-    f.render_widget(alarm_display, area);
+    f.render_widget(time_display, area);
 }
 
-fn render_f91w_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
-    let time_text = format_stopwatch_time(watch.stopwatch_time);
-    let status = if watch.stopwatch_running {
-        "RUN"
-    } else {
-        "STOP"
+// same module as the f-91w, but the metal case puts the day and date on top
+fn render_a158w_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let time_text = watch
+        .time_manager
+        .format_time(watch.settings.time_format_24h);
+    let day_text = watch.time_manager.format_day_of_week().to_uppercase();
+    let date_text = watch.time_manager.current_time.format("%e").to_string();
+    let face_print = match watch.model {
+        WatchModel::A168W => "ILLUMINATOR",
+        _ => "WATER RESIST",
     };
 
-    let stopwatch_display = Paragraph::new(vec![
+    let time_display = Paragraph::new(vec![
+        Line::from(vec![Span::styled(
+            format!("{:<3}{:>20}", day_text, date_text),
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            time_text,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "ALARM CHRONOGRAPH",
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            face_print,
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(time_display, area);
+}
+
+fn render_f91w_alarm_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let alarm_status = if watch.settings.alarm_enabled {
+        watch
+            .settings
+            .alarm_time
+            .clone()
+            .unwrap_or_else(|| "--:--".to_string())
+    } else {
+        "OFF".to_string()
+    };
+
+    let alarm_display = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "ALARM",
+            Style::default()
+                .fg(watch.theme.alarm)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            alarm_status,
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(alarm_display, area);
+}
+
+fn render_f91w_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    // a split freezes the display while the stopwatch keeps running
+    let time_text = format_stopwatch_time(watch.stopwatch_split.unwrap_or(watch.stopwatch_time));
+    let status = if watch.stopwatch_split.is_some() {
+        "SPLIT"
+    } else if watch.stopwatch_running {
+        "RUN"
+    } else {
+        "STOP"
+    };
+
+    let stopwatch_display = Paragraph::new(vec![
         Line::from(""),
         Line::from(vec![Span::styled(
             "STOPWATCH",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(watch.theme.label)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -527,7 +1123,7 @@ fn render_f91w_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             time_text,
             Style::default()
-                .fg(Color::Green)
+                .fg(watch.theme.digits)
                 .add_modifier(Modifier::BOLD),
         )])
         .alignment(Alignment::Center),
@@ -535,17 +1131,1757 @@ fn render_f91w_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
         Line::from(vec![Span::styled(
             status,
             if watch.stopwatch_running {
-                Style::default().fg(Color::Red)
+                Style::default().fg(watch.theme.running)
             } else {
-                Style::default().fg(Color::Blue)
+                Style::default().fg(watch.theme.stopped)
             },
         )])
         .alignment(Alignment::Center),
         Line::from(""),
-        Line::from("press 'S' start/stop, 'R' reset"),
-        Line::from("press 'M' for mode"),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Mode])),
     ])
     .block(Block::default());
 
     f.render_widget(stopwatch_display, area);
 }
+
+fn render_countdown_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    // round up so the display reads 00:00 only when time is really up
+    let total_seconds = watch.countdown_remaining.div_ceil(1000);
+    let time_text = format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60);
+    let status = match (
+        watch.countdown_running,
+        watch.settings.countdown_auto_repeat,
+    ) {
+        (true, true) => "RUN RPT",
+        (true, false) => "RUN",
+        (false, true) => "STOP RPT",
+        (false, false) => "STOP",
+    };
+
+    let countdown_display = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "TIMER",
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            time_text,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            status,
+            if watch.countdown_running {
+                Style::default().fg(watch.theme.running)
+            } else {
+                Style::default().fg(watch.theme.stopped)
+            },
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Alarm, Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(countdown_display, area);
+}
+
+// every daily alarm and the hourly signal, with the one being set highlighted
+fn render_multi_alarm_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let mut rows: Vec<(String, String, bool)> = watch
+        .daily_alarms()
+        .into_iter()
+        .enumerate()
+        .map(|(index, alarm)| {
+            let time = alarm.time.unwrap_or_else(|| "--:--".to_string());
+            let state = if alarm.enabled { "ON" } else { "OFF" };
+            (
+                format!("AL{}", index + 1),
+                format!("{} {}", time, state),
+                alarm.enabled,
+            )
+        })
+        .collect();
+    let signal = watch.settings.hourly_signal;
+    rows.push((
+        "SIG".to_string(),
+        format!("{:<3}", if signal { "ON" } else { "OFF" }),
+        signal,
+    ));
+
+    let mut lines = vec![Line::from("")];
+    lines.extend(
+        rows.into_iter()
+            .enumerate()
+            .map(|(index, (label, value, on))| {
+                let marker = if index == watch.alarm_slot { ">" } else { " " };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{}{:<4}", marker, label),
+                        Style::default()
+                            .fg(watch.theme.alarm)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{:>10}", value),
+                        if on {
+                            Style::default().fg(watch.theme.digits)
+                        } else {
+                            Style::default().fg(watch.theme.date)
+                        },
+                    ),
+                ])
+                .alignment(Alignment::Center)
+            }),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(watch.hint(&[Button::Reset, Button::StartStop])));
+    lines.push(Line::from(watch.hint(&[Button::Alarm, Button::Mode])));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+fn render_ca53w_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let time_text = watch
+        .time_manager
+        .format_time(watch.settings.time_format_24h);
+    let day_text = watch.time_manager.format_day_of_week().to_uppercase();
+    let date_text = watch
+        .time_manager
+        .format_date(watch.settings.date_format_us);
+
+    let time_display = Paragraph::new(vec![
+        Line::from(vec![Span::styled(
+            format!("{:<3}{:>20}", day_text, date_text),
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            time_text,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "CALCULATOR",
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(time_display, area);
+}
+
+fn render_calculator_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let calculator = &watch.calculator;
+
+    // operator, constant and error indicators sit above the digits
+    let indicators = format!(
+        "{} {} {}",
+        calculator
+            .operator()
+            .map(|operator| operator.symbol())
+            .unwrap_or(' '),
+        if calculator.has_constant() { 'K' } else { ' ' },
+        if calculator.is_error() { 'E' } else { ' ' },
+    );
+
+    // the keys actually bound in the keymap, in keypad order
+    let keypad: String = CalcKey::ALL
+        .iter()
+        .filter_map(|key| watch.keymap.key_for(&Action::Press(Button::Calc(*key))))
+        .map(|binding| binding.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let calculator_display = Paragraph::new(vec![
+        Line::from(vec![Span::styled(
+            "CAL",
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            format!("{:<22}", indicators),
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            format!("{:>22}", calculator.display()),
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            keypad,
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::Reset, Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(calculator_display, area);
+}
+
+fn render_dual_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let offset = watch.settings.dual_time_offset;
+    let time_text = watch
+        .time_manager
+        .format_dual_time(watch.settings.time_format_24h, offset);
+
+    let dual_time_display = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "DUAL TIME",
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            time_text,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!("+{}H", offset / 60),
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(dual_time_display, area);
+}
+
+// seven-segment digits three rows tall, for the big main display
+fn big_digits(text: &str) -> [String; 3] {
+    let mut rows = [String::new(), String::new(), String::new()];
+    for c in text.chars() {
+        let glyph: [&str; 3] = match c {
+            '0' => [" _ ", "| |", "|_|"],
+            '1' => ["   ", "  |", "  |"],
+            '2' => [" _ ", " _|", "|_ "],
+            '3' => [" _ ", " _|", " _|"],
+            '4' => ["   ", "|_|", "  |"],
+            '5' => [" _ ", "|_ ", " _|"],
+            '6' => [" _ ", "|_ ", "|_|"],
+            '7' => [" _ ", "  |", "  |"],
+            '8' => [" _ ", "|_|", "|_|"],
+            '9' => [" _ ", "|_|", " _|"],
+            '-' => ["   ", " _ ", "   "],
+            ':' => [" ", ".", "."],
+            '.' => [" ", " ", "."],
+            _ => ["   ", "   ", "   "],
+        };
+        for (row, part) in rows.iter_mut().zip(glyph) {
+            row.push_str(part);
+            row.push(' ');
+        }
+    }
+    rows
+}
+
+// 3x4 dot font drawn with braille cells, like the small dot-matrix on a g-shock
+fn dot_matrix(text: &str) -> String {
+    fn glyph(c: char) -> [u8; 4] {
+        match c.to_ascii_uppercase() {
+            'A' => [0b010, 0b101, 0b111, 0b101],
+            'B' => [0b110, 0b111, 0b101, 0b110],
+            'C' => [0b011, 0b100, 0b100, 0b011],
+            'D' => [0b110, 0b101, 0b101, 0b110],
+            'E' => [0b111, 0b110, 0b100, 0b111],
+            'F' => [0b111, 0b100, 0b110, 0b100],
+            'G' => [0b011, 0b100, 0b101, 0b011],
+            'H' => [0b101, 0b111, 0b101, 0b101],
+            'I' => [0b111, 0b010, 0b010, 0b111],
+            'J' => [0b001, 0b001, 0b101, 0b010],
+            'K' => [0b101, 0b110, 0b110, 0b101],
+            'L' => [0b100, 0b100, 0b100, 0b111],
+            'M' => [0b101, 0b111, 0b111, 0b101],
+            'N' => [0b110, 0b101, 0b101, 0b101],
+            'O' => [0b010, 0b101, 0b101, 0b010],
+            'P' => [0b110, 0b101, 0b110, 0b100],
+            'Q' => [0b010, 0b101, 0b111, 0b011],
+            'R' => [0b110, 0b101, 0b110, 0b101],
+            'S' => [0b011, 0b110, 0b001, 0b110],
+            'T' => [0b111, 0b010, 0b010, 0b010],
+            'U' => [0b101, 0b101, 0b101, 0b111],
+            'V' => [0b101, 0b101, 0b101, 0b010],
+            'W' => [0b101, 0b101, 0b111, 0b101],
+            'X' => [0b101, 0b010, 0b010, 0b101],
+            'Y' => [0b101, 0b101, 0b010, 0b010],
+            'Z' => [0b111, 0b001, 0b100, 0b111],
+            '0' => [0b111, 0b101, 0b101, 0b111],
+            '1' => [0b010, 0b110, 0b010, 0b111],
+            '2' => [0b110, 0b001, 0b010, 0b111],
+            '3' => [0b111, 0b011, 0b001, 0b111],
+            '4' => [0b101, 0b101, 0b111, 0b001],
+            '5' => [0b111, 0b110, 0b001, 0b110],
+            '6' => [0b100, 0b111, 0b101, 0b111],
+            '7' => [0b111, 0b001, 0b010, 0b010],
+            '8' => [0b111, 0b111, 0b101, 0b111],
+            '9' => [0b111, 0b101, 0b111, 0b001],
+            '-' => [0b000, 0b111, 0b000, 0b000],
+            ':' => [0b000, 0b010, 0b000, 0b010],
+            _ => [0; 4],
+        }
+    }
+
+    // each character is three dot columns plus one blank, two braille cells wide
+    let mut columns: Vec<[bool; 4]> = Vec::new();
+    for c in text.chars() {
+        let rows = glyph(c);
+        for bit in (0..3).rev() {
+            columns.push(rows.map(|row| row >> bit & 1 == 1));
+        }
+        columns.push([false; 4]);
+    }
+
+    // braille dots 1-2-3-7 run down the left column, 4-5-6-8 down the right
+    const LEFT: [u32; 4] = [0x01, 0x02, 0x04, 0x40];
+    const RIGHT: [u32; 4] = [0x08, 0x10, 0x20, 0x80];
+    columns
+        .chunks(2)
+        .map(|pair| {
+            let mut bits = 0;
+            for row in 0..4 {
+                if pair[0][row] {
+                    bits |= LEFT[row];
+                }
+                if pair.get(1).is_some_and(|column| column[row]) {
+                    bits |= RIGHT[row];
+                }
+            }
+            char::from_u32(0x2800 + bits).unwrap_or(' ')
+        })
+        .collect()
+}
+
+// every g-shock mode shares one layout: dot-matrix label on top, big digits below
+fn render_dw5600_lcd(
+    f: &mut Frame,
+    area: Rect,
+    watch: &Watch,
+    label: &str,
+    info: &str,
+    (big, small): (&str, &str),
+    hints: Vec<Line>,
+) {
+    let digits = big_digits(big);
+    let digit_style = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+
+    // the first row is left to the status indicators
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{:<12}", dot_matrix(label)),
+                Style::default().fg(watch.theme.label),
+            ),
+            Span::styled(
+                format!("{:>16}", dot_matrix(info)),
+                Style::default().fg(watch.theme.date),
+            ),
+        ])
+        .alignment(Alignment::Center),
+        Line::from(""),
+    ];
+    for (index, row) in digits.into_iter().enumerate() {
+        // the small digits sit on the baseline next to the big ones
+        let tail = if index == 2 {
+            format!(" {}", small)
+        } else {
+            " ".repeat(small.chars().count() + 1)
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(row, digit_style),
+                Span::styled(tail, Style::default().fg(watch.theme.digits)),
+            ])
+            .alignment(Alignment::Center),
+        );
+    }
+    lines.push(render_radio_indicator(watch));
+    let face = match watch.model {
+        WatchModel::GWM5610 => "MULTI BAND 6",
+        WatchModel::GLX5600 => "G-LIDE",
+        WatchModel::Custom(index) => &definition::loaded()[index].lcd.label,
+        _ => "SHOCK RESIST",
+    };
+    lines.push(
+        Line::from(vec![Span::styled(
+            face,
+            Style::default().fg(watch.theme.label),
+        )])
+        .alignment(Alignment::Center),
+    );
+    lines.push(Line::from(""));
+    lines.extend(hints);
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+fn render_dw5600_time_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let time = &watch.time_manager.current_time;
+    let day = watch.time_manager.format_day_of_week();
+    let date = if watch.settings.date_format_us {
+        time.format("%-m-%d").to_string()
+    } else {
+        time.format("%d-%-m").to_string()
+    };
+    let (big, small) = if watch.settings.time_format_24h {
+        (
+            time.format("%H:%M").to_string(),
+            time.format("%S").to_string(),
+        )
+    } else {
+        (
+            time.format("%l:%M").to_string(),
+            time.format("%S %p").to_string(),
+        )
+    };
+
+    render_dw5600_lcd(
+        f,
+        area,
+        watch,
+        &day[..2],
+        &date,
+        (&big, &small),
+        vec![Line::from(watch.hint(&[Button::Mode, Button::Light]))],
+    );
+}
+
+fn render_dw5600_stopwatch_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let reading = watch.stopwatch_split.unwrap_or(watch.stopwatch_time);
+    let total_seconds = reading / 1000;
+    // the first hour shows hundredths, after that hours take their place
+    let (big, small) = if total_seconds < 3600 {
+        (
+            format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60),
+            format!("{:02}", (reading % 1000) / 10),
+        )
+    } else {
+        (
+            format!("{}:{:02}", total_seconds / 3600, (total_seconds / 60) % 60),
+            format!("{:02}", total_seconds % 60),
+        )
+    };
+    let info = if watch.stopwatch_split.is_some() {
+        "SPL"
+    } else {
+        ""
+    };
+
+    render_dw5600_lcd(
+        f,
+        area,
+        watch,
+        "ST",
+        info,
+        (&big, &small),
+        vec![
+            Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+            Line::from(watch.hint(&[Button::Mode])),
+        ],
+    );
+}
+
+fn render_dw5600_timer_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    // round up so the display reads 00:00 only when time is really up
+    let total_seconds = watch.countdown_remaining.div_ceil(1000);
+    let big = format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60);
+    let info = if watch.settings.countdown_auto_repeat {
+        "RPT"
+    } else {
+        ""
+    };
+
+    render_dw5600_lcd(
+        f,
+        area,
+        watch,
+        "TR",
+        info,
+        (&big, ""),
+        vec![
+            Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+            Line::from(watch.hint(&[Button::Alarm, Button::Mode])),
+        ],
+    );
+}
+
+fn render_dw5600_alarm_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let big = watch
+        .settings
+        .alarm_time
+        .clone()
+        .unwrap_or_else(|| "--:--".to_string());
+    let info = match (watch.settings.alarm_enabled, watch.settings.hourly_signal) {
+        (true, true) => "ON SIG",
+        (true, false) => "ON",
+        (false, true) => "SIG",
+        (false, false) => "OFF",
+    };
+
+    render_dw5600_lcd(
+        f,
+        area,
+        watch,
+        "AL",
+        info,
+        (&big, ""),
+        vec![Line::from(watch.hint(&[Button::StartStop, Button::Mode]))],
+    );
+}
+
+// "RC" and the signal level flash while receiving, "RCVD" stays up for the day after a success
+fn render_radio_indicator(watch: &Watch) -> Line<'static> {
+    let Some(receiver) = watch.peripherals.receiver() else {
+        return Line::from("");
+    };
+    let now = watch.time_manager.current_time;
+    let text = if receiver.receiving() {
+        if now.timestamp_subsec_millis() < 500 {
+            format!("RC L{}", receiver.level())
+        } else {
+            format!("   L{}", receiver.level())
+        }
+    } else if receiver.received_on(now.date_naive()) {
+        "RCVD".to_string()
+    } else {
+        String::new()
+    };
+
+    Line::from(vec![Span::styled(
+        format!("{:>8}", text),
+        Style::default()
+            .fg(watch.theme.label)
+            .add_modifier(Modifier::BOLD),
+    )])
+    .alignment(Alignment::Right)
+}
+
+// shows when the watch last set itself from the time signal
+fn render_gwm5610_receive_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let Some(receiver) = watch.peripherals.receiver() else {
+        return;
+    };
+    let (info, big, small) = match receiver.last_success {
+        Some(time) => {
+            let date = if watch.settings.date_format_us {
+                time.format("%-m-%d").to_string()
+            } else {
+                time.format("%d-%-m").to_string()
+            };
+            let big = if watch.settings.time_format_24h {
+                time.format("%H:%M").to_string()
+            } else {
+                time.format("%l:%M").to_string()
+            };
+            (date, big, "")
+        }
+        None => (String::new(), "--:--".to_string(), ""),
+    };
+    let small = if receiver.receiving() {
+        "WORK"
+    } else if receiver.last_failed {
+        "ERR"
+    } else {
+        small
+    };
+
+    render_dw5600_lcd(
+        f,
+        area,
+        watch,
+        "RC",
+        &info,
+        (&big, small),
+        vec![Line::from(watch.hint(&[Button::Reset, Button::Mode]))],
+    );
+}
+
+// one record per screen: name on top, number below, with a blinking cursor while typing
+fn render_telememo_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let records = &watch.settings.telememo;
+    let telememo = &watch.telememo;
+    let full = records.len() >= databank::RECORDS;
+
+    let (name, number) = match (&telememo.editor, telememo.current(records)) {
+        (Some(editor), _) => (editor.name(), editor.number()),
+        (None, Some(record)) => (record.name.clone(), record.number.clone()),
+        (None, None) => (String::new(), String::new()),
+    };
+    let position = if full && telememo.current(records).is_none() {
+        "FULL".to_string()
+    } else if telememo.current(records).is_some() {
+        format!("{:02}/{:02}", telememo.index + 1, records.len())
+    } else {
+        format!("NEW {:02}", databank::RECORDS - records.len())
+    };
+
+    let digit_style = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+    let blink_on = watch.time_manager.current_time.timestamp_subsec_millis() < 500;
+    // the character under the cursor is shown reversed every other half second
+    let text_line = |text: &str, width: usize, field: Field| {
+        let chars: Vec<char> = format!("{:<width$}", text, width = width).chars().collect();
+        let cursor = telememo.editor.as_ref().and_then(|editor| {
+            (editor.field() == field && blink_on).then(|| match field {
+                Field::Name => editor.cursor,
+                Field::Number => editor.cursor - databank::NAME_LENGTH,
+            })
+        });
+        let spans: Vec<Span> = chars
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                let style = if Some(index) == cursor {
+                    digit_style.add_modifier(Modifier::REVERSED)
+                } else {
+                    digit_style
+                };
+                Span::styled(c.to_string(), style)
+            })
+            .collect();
+        Line::from(spans).alignment(Alignment::Center)
+    };
+
+    // the same buttons do different things while a record is being typed in
+    let hints = vec![
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Alarm, Button::Mode])),
+    ];
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "TELEMEMO ",
+                Style::default()
+                    .fg(watch.theme.label)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>7}", position),
+                if full {
+                    Style::default()
+                        .fg(watch.theme.alarm)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(watch.theme.date)
+                },
+            ),
+        ])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        text_line(&name, databank::NAME_LENGTH, Field::Name),
+        text_line(&number, databank::NUMBER_LENGTH, Field::Number),
+        Line::from(""),
+    ];
+    lines.extend(hints);
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// hands on the left, the digital module's little lcd on the right
+fn render_aq230_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let dial_area = Rect {
+        width: 22.min(area.width),
+        ..area
+    };
+    let lcd_area = Rect {
+        x: area.x + dial_area.width + 2,
+        width: area.width.saturating_sub(dial_area.width + 2),
+        ..area
+    };
+
+    let hands = watch.hands.time(watch.time_manager.current_time);
+    render_dial(f, dial_area, watch, hands, false);
+
+    let time_manager = &watch.time_manager;
+    let (label, main, detail) = match watch.aq230_mode {
+        AQ230Mode::Time => (
+            "TIME".to_string(),
+            time_manager.format_time(watch.settings.time_format_24h),
+            format!(
+                "{} {}",
+                time_manager.format_day_of_week().to_uppercase(),
+                time_manager.format_date(watch.settings.date_format_us)
+            ),
+        ),
+        AQ230Mode::DualTime => {
+            let offset = watch.settings.dual_time_offset;
+            (
+                "DUAL TIME".to_string(),
+                time_manager.format_dual_time(watch.settings.time_format_24h, offset),
+                format!("+{}H", offset / 60),
+            )
+        }
+        AQ230Mode::Alarm => (
+            "ALARM".to_string(),
+            watch
+                .settings
+                .alarm_time
+                .clone()
+                .unwrap_or_else(|| "--:--".to_string()),
+            match (watch.settings.alarm_enabled, watch.settings.hourly_signal) {
+                (true, true) => "ALM SIG",
+                (true, false) => "ALM",
+                (false, true) => "SIG",
+                (false, false) => "OFF",
+            }
+            .to_string(),
+        ),
+        AQ230Mode::Stopwatch => (
+            "STOPWATCH".to_string(),
+            format_stopwatch_time(watch.stopwatch_time),
+            if watch.stopwatch_running {
+                "RUN"
+            } else {
+                "STOP"
+            }
+            .to_string(),
+        ),
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            label,
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            main,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            detail,
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+    ];
+    let hint = watch.hint(&[Button::StartStop, Button::Reset]);
+    if !hint.is_empty() {
+        lines.push(Line::from(hint));
+    }
+    lines.push(Line::from(watch.hint(&[Button::Mode])));
+    // the crown only sets the hands, never the lcd
+    lines.extend(crown_hints(watch));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), lcd_area);
+}
+
+fn crown_hints(watch: &Watch) -> Vec<Line<'static>> {
+    if watch.hands.crown_pulled() {
+        vec![
+            Line::from(watch.hint(&[Button::CrownForward, Button::CrownBack])),
+            Line::from(watch.hint(&[Button::CrownPull])),
+        ]
+    } else {
+        vec![Line::from(watch.hint(&[Button::CrownPull]))]
+    }
+}
+
+// nothing but the dial; the second hand stops while the crown is out
+fn render_mq24_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let hints = crown_hints(watch);
+    let hint_height = (hints.len() as u16).min(area.height);
+    let dial_area = Rect {
+        height: area.height - hint_height,
+        ..area
+    };
+    let hint_area = Rect {
+        y: area.y + dial_area.height,
+        height: hint_height,
+        ..area
+    };
+
+    let hands = watch.hands.time(watch.time_manager.current_time);
+    render_dial(f, dial_area, watch, hands, true);
+    f.render_widget(Paragraph::new(hints), hint_area);
+}
+
+// a model from a definition file borrows the built-in screen for each function in its lcd style
+fn render_custom_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let Some(definition) = watch.model.definition() else {
+        return;
+    };
+    let function = definition.modes[watch.custom_mode].function;
+    if function == Function::Timekeeping && !definition.lcd.rows.is_empty() {
+        return render_layout_display(f, area, watch, definition);
+    }
+    match (
+        definition.lcd.style,
+        definition.modes[watch.custom_mode].function,
+    ) {
+        (LcdStyle::Classic, Function::Timekeeping) => render_f91w_time_display(f, area, watch),
+        (LcdStyle::Classic, Function::Alarm) => render_f91w_alarm_display(f, area, watch),
+        (LcdStyle::Classic, Function::Chronograph) => render_f91w_stopwatch_display(f, area, watch),
+        (LcdStyle::Classic, Function::Timer) => render_countdown_display(f, area, watch),
+        (LcdStyle::GShock, Function::Timekeeping) => render_dw5600_time_display(f, area, watch),
+        (LcdStyle::GShock, Function::Alarm) => render_dw5600_alarm_display(f, area, watch),
+        (LcdStyle::GShock, Function::Chronograph) => {
+            render_dw5600_stopwatch_display(f, area, watch)
+        }
+        (LcdStyle::GShock, Function::Timer) => render_dw5600_timer_display(f, area, watch),
+        (_, Function::WorldTime) => render_dual_time_display(f, area, watch),
+        (_, Function::Script) => render_script_display(f, area, watch),
+    }
+}
+
+// timekeeping laid out by the definition, a row of fields at a time under the indicators
+fn render_layout_display(f: &mut Frame, area: Rect, watch: &Watch, definition: &Definition) {
+    let time = &watch.time_manager;
+    let digits = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+    let date = Style::default().fg(watch.theme.date);
+    let label = Style::default().fg(watch.theme.label);
+    let hours = if watch.settings.time_format_24h {
+        "%H:%M"
+    } else {
+        "%I:%M"
+    };
+
+    let mut lines = vec![Line::from("")];
+    for row in &definition.lcd.rows {
+        if row.as_slice() == [LcdField::Digits] {
+            let text = time.current_time.format(hours).to_string();
+            lines.extend(
+                big_digits(&text)
+                    .map(|line| Line::styled(line, digits).alignment(Alignment::Center)),
+            );
+            continue;
+        }
+        let mut spans = Vec::new();
+        for field in row {
+            let (text, style) = match field {
+                LcdField::Time => (time.format_time(watch.settings.time_format_24h), digits),
+                LcdField::Digits => (time.current_time.format(hours).to_string(), digits),
+                LcdField::Seconds => (time.current_time.format("%S").to_string(), digits),
+                LcdField::Weekday => (time.format_day_of_week().to_uppercase(), date),
+                LcdField::Date => (time.format_date(watch.settings.date_format_us), date),
+                LcdField::Year => (time.current_time.format("%Y").to_string(), date),
+                LcdField::Mode => (definition.modes[watch.custom_mode].name.clone(), label),
+                LcdField::Label => (definition.lcd.label.clone(), label),
+            };
+            if !spans.is_empty() {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(text, style));
+        }
+        lines.push(Line::from(spans).alignment(Alignment::Center));
+    }
+    lines.resize(area.height.saturating_sub(1) as usize, Line::from(""));
+    lines.push(Line::from(watch.hint(&[Button::Mode])));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// rows as the script's draw() left them, with the mode hint kept at the bottom
+fn render_script_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let Some(Some(script)) = watch.scripts.get(watch.custom_mode) else {
+        return;
+    };
+    let height = area.height.saturating_sub(1) as usize;
+    let mut lines = vec![Line::from(""); height];
+    let digit_style = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+
+    for (row, segment) in &script.rows {
+        match segment {
+            Segment::Text(text) => {
+                if let Some(line) = lines.get_mut(*row) {
+                    *line = Line::from(Span::styled(text.clone(), digit_style));
+                }
+            }
+            Segment::Centre(text) => {
+                if let Some(line) = lines.get_mut(*row) {
+                    *line = Line::from(Span::styled(text.clone(), digit_style))
+                        .alignment(Alignment::Center);
+                }
+            }
+            Segment::Digits(text) => {
+                for (offset, digits) in big_digits(text).into_iter().enumerate() {
+                    if let Some(line) = lines.get_mut(row + offset) {
+                        *line = Line::from(Span::styled(digits, digit_style))
+                            .alignment(Alignment::Center);
+                    }
+                }
+            }
+        }
+    }
+    lines.push(Line::from(watch.hint(&[Button::Mode])));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// script errors and print() go on the row under the watch rather than on the lcd
+fn render_script_status(f: &mut Frame, size: Rect, watch_area: Rect, watch: &Watch) {
+    let Some(status) = watch.script_status() else {
+        return;
+    };
+    let area = Rect {
+        x: size.x,
+        y: watch_area.bottom(),
+        width: size.width,
+        height: 1,
+    }
+    .intersection(size);
+    f.render_widget(
+        Paragraph::new(status.to_string())
+            .style(Style::default().fg(watch.theme.alarm))
+            .alignment(Alignment::Center),
+        area,
+    );
+}
+
+// every segment is printed on the lcd; the game only decides which ones are dark
+fn render_game_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    const CAR: &str = "▗█▖";
+    const PLAYER: &str = "▟█▙";
+    let game = &watch.game;
+    let lit = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+    let ghost = Style::default()
+        .fg(watch.theme.date)
+        .add_modifier(Modifier::DIM);
+    let kerb = Style::default().fg(watch.theme.label);
+
+    let road = |segments: [bool; game::LANES], glyph: &'static str| {
+        let mut spans = vec![Span::styled("│", kerb)];
+        for on in segments {
+            spans.push(Span::styled(
+                format!(" {} ", glyph),
+                if on { lit } else { ghost },
+            ));
+            spans.push(Span::styled("│", kerb));
+        }
+        Line::from(spans).alignment(Alignment::Center)
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!(
+                "SC {:03}  LV {}  HI {:03}",
+                game.score,
+                game.level(),
+                watch.settings.game_high_score.max(game.score)
+            ),
+            kerb,
+        )])
+        .alignment(Alignment::Center),
+    ];
+    lines.extend(game.cars.iter().map(|row| road(*row, CAR)));
+    let mut player = [false; game::LANES];
+    player[game.player] = true;
+    lines.push(road(player, PLAYER));
+
+    // the words are printed segments too, only one of them dark at a time
+    let words = [
+        ("READY", !game.started),
+        ("PAUSE", game.started && !game.running && !game.over),
+        ("CRASH", game.over),
+    ];
+    let mut status = Vec::new();
+    for (word, on) in words {
+        status.push(Span::styled(word, if on { lit } else { ghost }));
+        status.push(Span::raw(" "));
+    }
+    status.pop();
+    lines.push(Line::from(status).alignment(Alignment::Center));
+    lines.push(Line::from(""));
+    lines.push(Line::from(watch.hint(&[Button::Reset, Button::StartStop])));
+    lines.push(Line::from(watch.hint(&[Button::Alarm, Button::Mode])));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// whatever the rom has put in lcd ram: eight seven-segment digits under a row of annunciators
+fn render_firmware_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let lit = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+    let ghost = Style::default()
+        .fg(watch.theme.date)
+        .add_modifier(Modifier::DIM);
+    let Some(mcu) = watch.peripherals.mcu() else {
+        return;
+    };
+    let segment = |index: usize, glyph: &'static str| {
+        let style = if mcu.segment(index) { lit } else { ghost };
+        Span::styled(glyph, style)
+    };
+
+    let mut annunciators = Vec::new();
+    for (index, word) in mcu::ANNUNCIATORS.iter().enumerate() {
+        annunciators.push(segment(mcu::ANNUNCIATOR_SEGMENT + index, word));
+        annunciators.push(Span::raw(" "));
+    }
+    annunciators.pop();
+    annunciators.push(Span::styled(if mcu.buzzer { " ♪" } else { "  " }, lit));
+
+    // a b c d e f g dp from segment 8 * digit on, drawn three rows tall
+    let mut rows: [Vec<Span>; 3] = Default::default();
+    for digit in 0..mcu::DIGITS {
+        let base = digit * 8;
+        rows[0].extend([Span::raw(" "), segment(base, "_"), Span::raw("  ")]);
+        rows[1].extend([
+            segment(base + 5, "|"),
+            segment(base + 6, "_"),
+            segment(base + 1, "|"),
+            Span::raw(" "),
+        ]);
+        rows[2].extend([
+            segment(base + 4, "|"),
+            segment(base + 3, "_"),
+            segment(base + 2, "|"),
+            segment(base + 7, "."),
+        ]);
+    }
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(annunciators).alignment(Alignment::Center),
+        Line::from(""),
+    ];
+    lines.extend(rows.map(|row| Line::from(row).alignment(Alignment::Center)));
+    lines.push(Line::from(""));
+    // what the buttons do is up to the rom
+    lines.push(Line::from(watch.hint(&[
+        Button::Mode,
+        Button::StartStop,
+        Button::Reset,
+        Button::Alarm,
+    ])));
+    lines.push(Line::from(watch.hint(&[Button::CrownPull])));
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// the on-screen keys, drawn in reverse video so they show in every theme; lit grid cells get a dot
+fn render_touch_keys(f: &mut Frame, area: Rect, watch: &Watch) {
+    for (rect, key) in touch::key_rects(area, &watch.touch_keys()) {
+        let lit = matches!(key, TouchKey::Cell(cell) if watch.grid.cells[cell]);
+        let (label, color) = if lit {
+            ("●".to_string(), watch.theme.digits)
+        } else {
+            (key.label(), watch.theme.label)
+        };
+        f.render_widget(
+            Paragraph::new(label)
+                .style(
+                    Style::default()
+                        .fg(color)
+                        .bg(watch.theme.lcd)
+                        .add_modifier(Modifier::REVERSED),
+                )
+                .alignment(Alignment::Center),
+            rect,
+        );
+    }
+}
+
+// a label and up to two readout lines above the keys, and the mode hint under them
+fn render_touch_lcd(f: &mut Frame, area: Rect, watch: &Watch, label: String, readout: [Line; 2]) {
+    let [first, second] = readout;
+    let lines = vec![
+        Line::from(vec![Span::styled(
+            label,
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        first,
+        second,
+    ];
+    f.render_widget(
+        Paragraph::new(lines),
+        Rect {
+            y: area.y + 1,
+            height: 3.min(area.height.saturating_sub(1)),
+            ..area
+        },
+    );
+    render_touch_keys(f, area, watch);
+    f.render_widget(
+        Paragraph::new(watch.hint(&[Button::Mode])),
+        Rect {
+            y: area.y + area.height.saturating_sub(1),
+            height: 1.min(area.height),
+            ..area
+        },
+    );
+}
+
+fn render_touch_calculator_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let calculator = &watch.calculator;
+    let operator = calculator
+        .operator()
+        .map(|operator| operator.symbol())
+        .unwrap_or(' ');
+    let width = area.width.saturating_sub(4) as usize;
+    render_touch_lcd(
+        f,
+        area,
+        watch,
+        "CALCULATOR".to_string(),
+        [
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(operator.to_string(), Style::default().fg(watch.theme.date)),
+                Span::styled(
+                    format!("{:>width$}", calculator.display(), width = width),
+                    Style::default()
+                        .fg(watch.theme.digits)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ])
+            .alignment(Alignment::Center),
+        ],
+    );
+}
+
+fn render_memo_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let memos = &watch.settings.memos;
+    let page = watch.memo_index.min(memos.len());
+    let text = memos.get(page).cloned().unwrap_or_default();
+    // what the grid would be read as, shown where the next letter goes
+    let guess = watch.grid.recognise().unwrap_or('_');
+    render_touch_lcd(
+        f,
+        area,
+        watch,
+        format!("MEMO {}/{}", page + 1, memos.len() + 1),
+        [
+            Line::from(vec![
+                Span::styled(
+                    text,
+                    Style::default()
+                        .fg(watch.theme.digits)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(guess.to_string(), Style::default().fg(watch.theme.date)),
+            ])
+            .alignment(Alignment::Center),
+            Line::from(""),
+        ],
+    );
+}
+
+fn render_schedule_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let schedule = &watch.settings.schedule;
+    let (label, when, text) = match watch.scheduler.current(schedule) {
+        Some(appointment) => (
+            format!("SCHEDULE {}/{}", watch.scheduler.index + 1, schedule.len()),
+            appointment
+                .time()
+                .map(|time| {
+                    time.format(if watch.settings.date_format_us {
+                        "%a %-m-%d %H:%M"
+                    } else {
+                        "%a %d-%-m %H:%M"
+                    })
+                    .to_string()
+                    .to_uppercase()
+                })
+                .unwrap_or_else(|| appointment.time.clone()),
+            appointment.text.clone(),
+        ),
+        None => (
+            "SCHEDULE".to_string(),
+            "NO ENTRIES".to_string(),
+            String::new(),
+        ),
+    };
+    render_touch_lcd(
+        f,
+        area,
+        watch,
+        label,
+        [
+            Line::from(Span::styled(
+                when,
+                Style::default()
+                    .fg(watch.theme.digits)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center),
+            Line::from(Span::styled(text, Style::default().fg(watch.theme.date)))
+                .alignment(Alignment::Center),
+        ],
+    );
+}
+
+fn render_remote_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let (device, code, detail) = match watch.ir_selection() {
+        Some((device, code)) => (
+            device.name,
+            code.name,
+            format!(
+                "{} {:02X} {:02X}",
+                code.protocol.name(),
+                code.address,
+                code.command
+            ),
+        ),
+        None => ("----".to_string(), "----".to_string(), String::new()),
+    };
+    // a write error takes the place of the send count
+    let Some(remote) = watch.peripherals.remote() else {
+        return;
+    };
+    let status = match &remote.last_error {
+        Some(error) => Span::styled(
+            format!("ERR {}", error),
+            Style::default().fg(watch.theme.alarm),
+        ),
+        None => Span::styled(
+            format!("TX {}", remote.sent),
+            Style::default().fg(watch.theme.running),
+        ),
+    };
+
+    let remote_display = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!("REMOTE {}", device),
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            code,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            detail,
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(status).alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset, Button::Alarm])),
+        Line::from(watch.hint(&[Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(remote_display, area);
+}
+
+fn render_learn_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let Some(remote) = watch.peripherals.remote() else {
+        return;
+    };
+    let (position, name, detail) = match remote.current_learned() {
+        Some(learned) => (
+            format!("{}/{}", remote.learn_index + 1, remote.learned.len()),
+            learned.name.clone(),
+            match learned.code {
+                Some((protocol, address, command)) => {
+                    format!("{} {:02X} {:02X}", protocol.name(), address, command)
+                }
+                None => "UNKNOWN".to_string(),
+            },
+        ),
+        None => (String::new(), "NO FILE".to_string(), String::new()),
+    };
+
+    let learn_display = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!("LEARN {}", position),
+            Style::default()
+                .fg(watch.theme.label)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            name,
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(vec![Span::styled(
+            detail,
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Alarm])),
+        Line::from(watch.hint(&[Button::Mode])),
+    ])
+    .block(Block::default());
+
+    f.render_widget(learn_display, area);
+}
+
+// the day's tide at the chosen port as bars, with the moon beside it
+fn render_tide_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let port = watch.tide_port();
+    let viewed = watch.time_manager.current_time + watch.almanac_offset;
+    let utc = viewed.to_utc();
+
+    // one bar per hour of the viewed day
+    let midnight = utc - TimeDelta::seconds(viewed.num_seconds_from_midnight() as i64);
+    let levels: Vec<f64> = (0..24)
+        .map(|hour| port.tide_level(midnight + TimeDelta::hours(hour)))
+        .collect();
+    let hour = viewed.hour() as usize;
+
+    let header = Line::from(vec![
+        Span::styled(
+            format!("{:<12}", dot_matrix("TIDE")),
+            Style::default().fg(watch.theme.label),
+        ),
+        Span::styled(
+            format!(
+                "{:>24}",
+                format!("{} {}", port.code, viewed.format("%-m-%d %H:00"))
+            ),
+            Style::default().fg(watch.theme.date),
+        ),
+    ])
+    .alignment(Alignment::Center);
+    f.render_widget(
+        Paragraph::new(vec![Line::from(""), header]),
+        Rect {
+            height: 2.min(area.height),
+            ..area
+        },
+    );
+
+    // the moon takes a square of braille dots, eight columns by four rows
+    let chart_top = area.y + 2;
+    let chart_width: u16 = 8 + 2 + 24;
+    let chart_x = area.x + area.width.saturating_sub(chart_width) / 2;
+    let moon_area = Rect {
+        x: chart_x,
+        y: chart_top,
+        width: 8,
+        height: 4,
+    }
+    .intersection(area);
+    render_moon(f, moon_area, watch, tide::moon_age(utc));
+
+    let mut graph: Vec<Line> = tide_graph(&levels, 4)
+        .into_iter()
+        .map(|row| {
+            Line::from(
+                row.chars()
+                    .enumerate()
+                    .map(|(column, bar)| {
+                        let color = if column == hour {
+                            watch.theme.digits
+                        } else {
+                            watch.theme.date
+                        };
+                        Span::styled(bar.to_string(), Style::default().fg(color))
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    graph.push(Line::from(Span::styled(
+        format!("{}▲", " ".repeat(hour)),
+        Style::default().fg(watch.theme.digits),
+    )));
+    f.render_widget(
+        Paragraph::new(graph),
+        Rect {
+            x: chart_x + 10,
+            y: chart_top,
+            width: 24,
+            height: 5,
+        }
+        .intersection(area),
+    );
+
+    let high = port.next_high_water(utc).with_timezone(&viewed.timezone());
+    let low = port.next_low_water(utc).with_timezone(&viewed.timezone());
+    let lines = vec![
+        Line::from(vec![Span::styled(
+            format!("HI {}   LO {}", high.format("%H:%M"), low.format("%H:%M")),
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset, Button::Alarm])),
+        Line::from(watch.hint(&[Button::Mode])),
+    ];
+    f.render_widget(
+        Paragraph::new(lines),
+        Rect {
+            y: chart_top + 5,
+            height: area.height.saturating_sub(7),
+            ..area
+        }
+        .intersection(area),
+    );
+}
+
+// bars `rows` tall, top row first, drawn in eighths of a row
+fn tide_graph(levels: &[f64], rows: usize) -> Vec<String> {
+    const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let eighths: Vec<usize> = levels
+        .iter()
+        .map(|level| (((level + 1.0) / 2.0 * (rows * 8) as f64).round() as usize).max(1))
+        .collect();
+    (0..rows)
+        .map(|row| {
+            let base = (rows - 1 - row) * 8;
+            eighths
+                .iter()
+                .map(|height| BARS[height.saturating_sub(base).min(8)])
+                .collect()
+        })
+        .collect()
+}
+
+fn render_moon_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let viewed = watch.time_manager.current_time + watch.almanac_offset;
+    let age = tide::moon_age(viewed.to_utc());
+    render_moon(
+        f,
+        Rect {
+            x: area.x + 2,
+            y: area.y + 2,
+            width: 12,
+            height: 6,
+        }
+        .intersection(area),
+        watch,
+        age,
+    );
+
+    let text_area = Rect {
+        x: area.x + 16,
+        width: area.width.saturating_sub(16),
+        ..area
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            dot_matrix("MOON"),
+            Style::default().fg(watch.theme.label),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            viewed
+                .format(if watch.settings.date_format_us {
+                    "%a %-m-%d"
+                } else {
+                    "%a %d-%-m"
+                })
+                .to_string()
+                .to_uppercase(),
+            Style::default().fg(watch.theme.date),
+        )),
+        Line::from(Span::styled(
+            format!("AGE {:.1}", age),
+            Style::default()
+                .fg(watch.theme.digits)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            tide::phase_name(age),
+            Style::default().fg(watch.theme.digits),
+        )),
+    ];
+    f.render_widget(Paragraph::new(lines), text_area);
+
+    let hints = vec![
+        Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+        Line::from(watch.hint(&[Button::Mode])),
+    ];
+    f.render_widget(
+        Paragraph::new(hints),
+        Rect {
+            y: area.y + area.height.saturating_sub(2),
+            height: 2.min(area.height),
+            ..area
+        },
+    );
+}
+
+// the lit part of the moon as seen from the north, growing from the right
+fn render_moon(f: &mut Frame, area: Rect, watch: &Watch, age: f64) {
+    use std::f64::consts::PI;
+
+    let phase = (2.0 * PI * age / tide::SYNODIC_MONTH_DAYS).cos();
+    let waxing = age < tide::SYNODIC_MONTH_DAYS / 2.0;
+    let mut lit = Vec::new();
+    for row in 0..=40 {
+        let y = row as f64 / 20.0 - 1.0;
+        let half_width = (1.0 - y * y).sqrt();
+        // the terminator is half an ellipse across the disc
+        let terminator = half_width * phase;
+        for column in 0..=40 {
+            let x = column as f64 / 20.0 - 1.0;
+            let inside = x.abs() <= half_width;
+            let on = if waxing {
+                x > terminator
+            } else {
+                x < -terminator
+            };
+            if inside && on {
+                lit.push((x, y));
+            }
+        }
+    }
+
+    let moon = Canvas::default()
+        .marker(symbols::Marker::Braille)
+        .background_color(watch.theme.lcd)
+        .x_bounds([-1.0, 1.0])
+        .y_bounds([-1.0, 1.0])
+        .paint(|ctx| {
+            ctx.draw(&Circle {
+                x: 0.0,
+                y: 0.0,
+                radius: 0.98,
+                color: watch.theme.label,
+            });
+            ctx.draw(&Points {
+                coords: &lit,
+                color: watch.theme.digits,
+            });
+        });
+    f.render_widget(moon, area);
+}
+
+// one sensor at a time, big readout in the middle and the details under it
+fn render_prg240_display(f: &mut Frame, area: Rect, watch: &Watch) {
+    let Some(sensors) = watch.peripherals.sensors() else {
+        return;
+    };
+    let calibration = &watch.settings.calibration;
+    let time = &watch.time_manager.current_time;
+
+    let (label, info, big, unit, detail) = match watch.prg240_mode {
+        PRG240Mode::Time => (
+            "TIME",
+            time.format("%a %d").to_string().to_uppercase(),
+            time.format(if watch.settings.time_format_24h {
+                "%H:%M"
+            } else {
+                "%l:%M"
+            })
+            .to_string(),
+            time.format("%S").to_string(),
+            String::new(),
+        ),
+        PRG240Mode::Compass => {
+            let heading = sensors.heading(calibration);
+            let detail = match sensors.bearing_memory {
+                // which way to turn to get back on the marked bearing
+                Some(memory) => {
+                    let turn = (memory - heading + 540.0).rem_euclid(360.0) - 180.0;
+                    let arrow = if turn < 0.0 { "◀" } else { "▶" };
+                    format!(
+                        "{}  {} {:.0}°",
+                        sensors::direction(heading),
+                        arrow,
+                        turn.abs()
+                    )
+                }
+                None => sensors::direction(heading).to_string(),
+            };
+            (
+                "COMPASS",
+                sensors
+                    .bearing_memory
+                    .map(|memory| format!("MEM {:03.0}°", memory))
+                    .unwrap_or_default(),
+                format!("{:03.0}", heading.round() % 360.0),
+                "°".to_string(),
+                detail,
+            )
+        }
+        PRG240Mode::Altimeter => {
+            let altitude = sensors.altitude(calibration);
+            match sensors.altitude_reference {
+                Some(reference) => (
+                    "ALTI",
+                    "REL".to_string(),
+                    format!("{:.0}", altitude - reference),
+                    "m".to_string(),
+                    format!("{:.0} m, from {:.0} m", altitude, reference),
+                ),
+                None => (
+                    "ALTI",
+                    String::new(),
+                    format!("{:.0}", altitude),
+                    "m".to_string(),
+                    String::new(),
+                ),
+            }
+        }
+        PRG240Mode::Barometer => (
+            "BARO",
+            String::new(),
+            format!("{:.0}", sensors.pressure(calibration)),
+            "hPa".to_string(),
+            pressure_graph(&sensors.pressure_trend()),
+        ),
+        PRG240Mode::Thermometer => (
+            "THERMO",
+            String::new(),
+            format!("{:.1}", sensors.temperature(calibration)),
+            "°C".to_string(),
+            String::new(),
+        ),
+    };
+
+    // while calibrating the detail row shows the offset being set instead
+    let (info, detail) = if watch.calibrating {
+        let offset = match watch.prg240_mode {
+            PRG240Mode::Compass => format!("{:+.0}°", calibration.heading),
+            PRG240Mode::Altimeter => format!("{:+.0} m", calibration.altitude),
+            PRG240Mode::Barometer => format!("{:+.0} hPa", calibration.pressure),
+            PRG240Mode::Thermometer => format!("{:+.1} °C", calibration.temperature),
+            PRG240Mode::Time => String::new(),
+        };
+        let flash_on = time.timestamp_subsec_millis() < 500;
+        (
+            if flash_on { "CAL" } else { "" }.to_string(),
+            format!("offset {}", offset),
+        )
+    } else {
+        (info, detail)
+    };
+
+    let hints = if watch.calibrating {
+        vec![
+            Line::from(watch.hint(&[Button::StartStop, Button::Reset])),
+            Line::from(watch.hint(&[Button::Alarm])),
+        ]
+    } else {
+        vec![
+            Line::from(watch.hint(&[Button::StartStop, Button::Alarm])),
+            Line::from(watch.hint(&[Button::Mode])),
+        ]
+    };
+
+    let digits = big_digits(&big);
+    let digit_style = Style::default()
+        .fg(watch.theme.digits)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{:<10}", label),
+                Style::default()
+                    .fg(watch.theme.label)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>14}", info),
+                Style::default().fg(watch.theme.date),
+            ),
+        ])
+        .alignment(Alignment::Center),
+    ];
+    for (index, row) in digits.into_iter().enumerate() {
+        let tail = if index == 2 {
+            format!(" {:<3}", unit)
+        } else {
+            " ".repeat(4)
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(row, digit_style),
+                Span::styled(tail, Style::default().fg(watch.theme.digits)),
+            ])
+            .alignment(Alignment::Center),
+        );
+    }
+    lines.push(
+        Line::from(vec![Span::styled(
+            detail,
+            Style::default().fg(watch.theme.date),
+        )])
+        .alignment(Alignment::Center),
+    );
+    lines.push(Line::from(""));
+    lines.extend(hints);
+
+    f.render_widget(Paragraph::new(lines).block(Block::default()), area);
+}
+
+// one bar per hourly sample, a level per hPa above or below the latest
+fn pressure_graph(trend: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let mut graph: String = trend
+        .iter()
+        .map(|change| BARS[(3.0 + change.round()).clamp(0.0, 7.0) as usize])
+        .collect();
+    // rising pressure usually means fairer weather
+    if let [.., earlier, _, _, latest] = trend {
+        graph.push_str(match latest - earlier {
+            change if change > 1.0 => " ↗",
+            change if change < -1.0 => " ↘",
+            _ => " →",
+        });
+    }
+    graph
+}
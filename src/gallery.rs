@@ -0,0 +1,45 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::watch::WatchModel;
+
+// the model picker shown when no model is asked for; the watches it previews run behind it
+#[derive(Debug, Default)]
+pub struct Gallery {
+    pub selected: usize, // index into the models on show
+}
+
+// where a key press leaves the gallery
+pub enum Choice {
+    Browsing,
+    Picked(usize),
+    Quit,
+}
+
+impl Gallery {
+    // starts on the model picked last time, or the f-91w the first time round
+    pub fn new(models: &[WatchModel], remembered: Option<&str>) -> Self {
+        let name = remembered.unwrap_or(WatchModel::F91W.name());
+        let selected = models
+            .iter()
+            .position(|model| model.name() == name)
+            .unwrap_or(0);
+        Self { selected }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, count: usize) -> Choice {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Choice::Quit,
+            KeyCode::Enter | KeyCode::Char(' ') => return Choice::Picked(self.selected),
+            KeyCode::Up | KeyCode::Left | KeyCode::Char('k') => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(count - 1);
+            }
+            KeyCode::Down | KeyCode::Right | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1) % count;
+            }
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = count - 1,
+            _ => {}
+        }
+        Choice::Browsing
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const LANES: usize = 3;
+// rows of car segments above the player's row
+pub const ROWS: usize = 5;
+const CARS_PER_LEVEL: u32 = 10;
+pub const MAX_LEVEL: u32 = 9;
+
+// a car race on a fixed lcd: oncoming cars move down the segments and the player's car dodges them
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub player: usize,               // lane of the player's car
+    pub cars: [[bool; LANES]; ROWS], // lit car segments, top row first
+    pub score: u32,
+    pub running: bool,
+    pub started: bool, // false until the first press, so a paused game isn't taken for a new one
+    pub over: bool,
+    last_step: Option<Instant>,
+    seed: u64,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(1);
+        Self {
+            player: LANES / 2,
+            cars: [[false; LANES]; ROWS],
+            score: 0,
+            running: false,
+            started: false,
+            over: false,
+            last_step: None,
+            seed: seed | 1,
+        }
+    }
+
+    // every ten cars passed the level goes up and the cars come faster
+    pub fn level(&self) -> u32 {
+        (1 + self.score / CARS_PER_LEVEL).min(MAX_LEVEL)
+    }
+
+    fn step_interval(&self) -> Duration {
+        Duration::from_millis(650 - 55 * self.level() as u64)
+    }
+
+    // starts a new game, or pauses and resumes one in progress
+    pub fn start_pause(&mut self) {
+        if self.over || !self.started {
+            *self = Self {
+                running: true,
+                started: true,
+                seed: self.seed,
+                ..Self::new()
+            };
+        } else {
+            self.running = !self.running;
+        }
+        self.last_step = None;
+    }
+
+    pub fn steer(&mut self, right: bool) {
+        if !self.running {
+            return;
+        }
+        self.player = if right {
+            (self.player + 1).min(LANES - 1)
+        } else {
+            self.player.saturating_sub(1)
+        };
+    }
+
+    pub fn update(&mut self, now: Instant) {
+        if !self.running {
+            return;
+        }
+        let last_step = *self.last_step.get_or_insert(now);
+        if now.duration_since(last_step) < self.step_interval() {
+            return;
+        }
+        self.last_step = Some(now);
+
+        // the bottom row reaches the player: one in the player's lane is a crash, the rest score
+        let passing = self.cars[ROWS - 1];
+        if passing[self.player] {
+            self.crash();
+            return;
+        }
+        self.score += passing.iter().filter(|&&car| car).count() as u32;
+
+        self.cars.rotate_right(1);
+        self.cars[0] = [false; LANES];
+        // a new car now and then, never straight after another so there's always a way through
+        if !self.cars[1].contains(&true) && self.random() < 0.7 {
+            let lane = (self.random() * LANES as f64) as usize % LANES;
+            self.cars[0][lane] = true;
+        }
+    }
+
+    fn crash(&mut self) {
+        self.running = false;
+        self.over = true;
+    }
+
+    // xorshift, plenty for picking lanes
+    fn random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a game a few steps in, with a car about to pass in each lane but the player's
+    fn game_with_cars(player: usize) -> Game {
+        let mut game = Game::new();
+        game.start_pause();
+        game.player = player;
+        game.cars[ROWS - 1] = [true; LANES];
+        game.cars[ROWS - 1][player] = false;
+        game
+    }
+
+    // one step's worth of time after the first update sets the clock going
+    fn step(game: &mut Game, start: Instant) {
+        game.update(start);
+        game.update(start + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn pause_then_resume_keeps_the_game() {
+        let mut game = Game::new();
+        game.start_pause();
+        assert!(game.running && game.started);
+        game.steer(false);
+        game.cars[1][2] = true;
+        let (player, cars) = (game.player, game.cars);
+
+        game.start_pause();
+        assert!(!game.running && !game.over);
+        game.start_pause();
+        assert!(game.running);
+        assert_eq!(game.player, player);
+        assert_eq!(game.cars, cars);
+    }
+
+    #[test]
+    fn steering_only_while_running() {
+        let mut game = Game::new();
+        game.steer(true);
+        assert_eq!(game.player, LANES / 2);
+        game.start_pause();
+        for _ in 0..LANES {
+            game.steer(true);
+        }
+        assert_eq!(game.player, LANES - 1);
+    }
+
+    #[test]
+    fn passing_cars_score() {
+        let mut game = game_with_cars(0);
+        step(&mut game, Instant::now());
+        assert_eq!(game.score, LANES as u32 - 1);
+        assert!(game.running && !game.over);
+    }
+
+    #[test]
+    fn a_car_in_the_players_lane_crashes() {
+        let mut game = game_with_cars(0);
+        game.player = 1;
+        step(&mut game, Instant::now());
+        assert!(game.over && !game.running);
+        assert_eq!(game.score, 0);
+
+        // the next press starts afresh
+        game.start_pause();
+        assert!(game.running && !game.over);
+        assert_eq!(game.cars, [[false; LANES]; ROWS]);
+    }
+
+    #[test]
+    fn level_steps_every_ten_cars() {
+        let mut game = Game::new();
+        for (score, level) in [
+            (0, 1),
+            (9, 1),
+            (10, 2),
+            (79, 8),
+            (80, MAX_LEVEL),
+            (500, MAX_LEVEL),
+        ] {
+            game.score = score;
+            assert_eq!(game.level(), level, "score {}", score);
+        }
+    }
+}
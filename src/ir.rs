@@ -0,0 +1,469 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// timings are in microseconds, alternating pulse and space and starting with a pulse
+const NEC_LEADER: (u32, u32) = (9000, 4500);
+const NEC_BIT: u32 = 562;
+const NEC_ONE: u32 = 1687;
+const RC5_HALF_BIT: u32 = 889;
+// a space this long in a mode2 file ends one code
+const CODE_GAP: u32 = 20_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Nec, // pulse distance, 8 or 16-bit address and 8-bit command
+    Rc5, // manchester, 5-bit address and 7-bit command
+}
+
+impl Protocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::Nec => "NEC",
+            Protocol::Rc5 => "RC5",
+        }
+    }
+}
+
+// one button on a remote
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IrCode {
+    pub name: String,
+    pub protocol: Protocol,
+    pub address: u16,
+    pub command: u16,
+}
+
+impl IrCode {
+    fn new(name: &str, protocol: Protocol, address: u16, command: u16) -> Self {
+        Self {
+            name: name.to_string(),
+            protocol,
+            address,
+            command,
+        }
+    }
+
+    // rc5 flips the toggle bit on each new press so a held button can be told from two presses
+    pub fn timings(&self, toggle: bool) -> Vec<u32> {
+        match self.protocol {
+            Protocol::Nec => nec_timings(self.address, self.command),
+            Protocol::Rc5 => rc5_timings(self.address, self.command, toggle),
+        }
+    }
+}
+
+// a device in the watch's table, stepped through with the alarm button
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Device {
+    pub name: String,
+    pub codes: Vec<IrCode>,
+}
+
+pub const LEARNED_DEVICE: &str = "LEARNED";
+
+pub fn default_devices() -> Vec<Device> {
+    let nec = |name, command| IrCode::new(name, Protocol::Nec, 0x04, command);
+    let rc5 = |name, command| IrCode::new(name, Protocol::Rc5, 0x00, command);
+    vec![
+        Device {
+            name: "TV".to_string(),
+            codes: vec![
+                nec("POWER", 0x08),
+                nec("CH+", 0x00),
+                nec("CH-", 0x01),
+                nec("VOL+", 0x02),
+                nec("VOL-", 0x03),
+                nec("MUTE", 0x09),
+            ],
+        },
+        Device {
+            name: "TV2".to_string(),
+            codes: vec![
+                rc5("POWER", 12),
+                rc5("CH+", 32),
+                rc5("CH-", 33),
+                rc5("VOL+", 16),
+                rc5("VOL-", 17),
+                rc5("MUTE", 13),
+            ],
+        },
+    ]
+}
+
+fn nec_timings(address: u16, command: u16) -> Vec<u32> {
+    // plain nec sends the address and command each followed by its inverse
+    let address_bits = if address > 0xFF {
+        address as u32
+    } else {
+        address as u32 | ((!address & 0xFF) as u32) << 8
+    };
+    let bits = address_bits | ((command & 0xFF) as u32) << 16 | ((!command & 0xFF) as u32) << 24;
+
+    let mut timings = vec![NEC_LEADER.0, NEC_LEADER.1];
+    for bit in 0..32 {
+        timings.push(NEC_BIT);
+        timings.push(if bits >> bit & 1 == 1 {
+            NEC_ONE
+        } else {
+            NEC_BIT
+        });
+    }
+    timings.push(NEC_BIT);
+    timings
+}
+
+fn rc5_timings(address: u16, command: u16, toggle: bool) -> Vec<u32> {
+    // start bit, field bit (the inverse of command bit 6), toggle, then address and command
+    let mut bits = vec![true, command & 0x40 == 0, toggle];
+    bits.extend((0..5).rev().map(|bit| address >> bit & 1 == 1));
+    bits.extend((0..6).rev().map(|bit| command >> bit & 1 == 1));
+
+    // a one goes from space to pulse halfway through the bit, a zero the other way round
+    let halves: Vec<bool> = bits.iter().flat_map(|&bit| [!bit, bit]).collect();
+    let mut timings: Vec<u32> = Vec::new();
+    let mut level = true;
+    for half in halves.into_iter().skip_while(|pulse| !pulse) {
+        match timings.last_mut() {
+            Some(last) if half == level => *last += RC5_HALF_BIT,
+            _ => {
+                timings.push(RC5_HALF_BIT);
+                level = half;
+            }
+        }
+    }
+    // a trailing space isn't sent
+    if !level {
+        timings.pop();
+    }
+    timings
+}
+
+// within 30%, which covers most receivers' sloppiness
+fn near(value: u32, target: u32) -> bool {
+    value.abs_diff(target) * 10 <= target * 3
+}
+
+// works out the protocol, address and command from raw timings
+pub fn decode(timings: &[u32]) -> Option<(Protocol, u16, u16)> {
+    decode_nec(timings).or_else(|| decode_rc5(timings))
+}
+
+fn decode_nec(timings: &[u32]) -> Option<(Protocol, u16, u16)> {
+    if timings.len() < 67 || !near(timings[0], NEC_LEADER.0) || !near(timings[1], NEC_LEADER.1) {
+        return None;
+    }
+    let mut bits = 0u32;
+    for bit in 0..32 {
+        let space = timings[3 + bit * 2];
+        if near(space, NEC_ONE) {
+            bits |= 1 << bit;
+        } else if !near(space, NEC_BIT) {
+            return None;
+        }
+    }
+    let command = (bits >> 16 & 0xFF) as u16;
+    if (bits >> 24 & 0xFF) as u16 != !command & 0xFF {
+        return None;
+    }
+    let low = (bits & 0xFF) as u16;
+    let high = (bits >> 8 & 0xFF) as u16;
+    let address = if high == !low & 0xFF {
+        low
+    } else {
+        (bits & 0xFFFF) as u16
+    };
+    Some((Protocol::Nec, address, command))
+}
+
+fn decode_rc5(timings: &[u32]) -> Option<(Protocol, u16, u16)> {
+    // the start bit's first half is a space the receiver never sees
+    let mut halves = vec![false];
+    for (index, &duration) in timings.iter().enumerate() {
+        let count = if near(duration, RC5_HALF_BIT) {
+            1
+        } else if near(duration, 2 * RC5_HALF_BIT) {
+            2
+        } else {
+            return None;
+        };
+        halves.extend(std::iter::repeat_n(index % 2 == 0, count));
+    }
+    if halves.len() > 28 {
+        return None;
+    }
+    halves.resize(28, false);
+
+    let bits = halves
+        .chunks(2)
+        .map(|half| match half {
+            [false, true] => Some(true),
+            [true, false] => Some(false),
+            _ => None,
+        })
+        .collect::<Option<Vec<bool>>>()?;
+    let value = |bits: &[bool]| {
+        bits.iter()
+            .fold(0u16, |value, &bit| value << 1 | bit as u16)
+    };
+    let address = value(&bits[3..8]);
+    let command = value(&bits[8..14]) | if bits[1] { 0 } else { 0x40 };
+    Some((Protocol::Rc5, address, command))
+}
+
+// a raw code read back from a lirc file, and what it turned out to be
+#[derive(Debug, Clone)]
+pub struct Learned {
+    pub name: String,
+    pub code: Option<(Protocol, u16, u16)>,
+}
+
+// lircd.conf raw_codes sections, or mode2 pulse/space text with "# name" comments
+pub fn read_lirc(path: &Path) -> Result<Vec<Learned>> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("can't read lirc file {}", path.display()))?;
+    let raw = if data.contains("begin raw_codes") {
+        parse_raw_codes(&data)
+    } else {
+        parse_mode2(&data)
+    }
+    .with_context(|| format!("invalid lirc file {}", path.display()))?;
+    if raw.is_empty() {
+        bail!("lirc file {} has no codes", path.display());
+    }
+    Ok(raw
+        .into_iter()
+        .map(|(name, timings)| Learned {
+            name,
+            code: decode(&timings),
+        })
+        .collect())
+}
+
+fn parse_raw_codes(data: &str) -> Result<Vec<(String, Vec<u32>)>> {
+    let mut codes: Vec<(String, Vec<u32>)> = Vec::new();
+    let mut inside = false;
+    for line in data.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("begin"), Some("raw_codes")) => inside = true,
+            (Some("end"), Some("raw_codes")) => inside = false,
+            (Some("name"), Some(name)) if inside => codes.push((name.to_string(), Vec::new())),
+            (Some(_), _) if inside => {
+                let Some((_, timings)) = codes.last_mut() else {
+                    bail!("timings before the first name");
+                };
+                for word in line.split_whitespace() {
+                    timings.push(
+                        word.parse()
+                            .with_context(|| format!("bad timing '{}'", word))?,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(codes)
+}
+
+fn parse_mode2(data: &str) -> Result<Vec<(String, Vec<u32>)>> {
+    let mut codes: Vec<(String, Vec<u32>)> = Vec::new();
+    let mut name = None;
+    let mut timings: Vec<u32> = Vec::new();
+    let mut finish = |name: &mut Option<String>, timings: &mut Vec<u32>| {
+        if !timings.is_empty() {
+            let fallback = format!("CODE{}", codes.len() + 1);
+            codes.push((name.take().unwrap_or(fallback), std::mem::take(timings)));
+        }
+    };
+    for (number, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            finish(&mut name, &mut timings);
+            name = comment.split_whitespace().last().map(str::to_string);
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let (kind, duration) = match (words.next(), words.next()) {
+            (Some(kind), Some(duration)) => (kind, duration),
+            (None, _) => continue,
+            _ => bail!("line {} isn't 'pulse N' or 'space N'", number + 1),
+        };
+        let duration: u32 = duration
+            .parse()
+            .with_context(|| format!("line {} has a bad duration", number + 1))?;
+        match kind {
+            "pulse" => timings.push(duration),
+            // a long gap ends the code; spaces before the first pulse don't count
+            "space" if duration >= CODE_GAP => finish(&mut name, &mut timings),
+            "space" if !timings.is_empty() => timings.push(duration),
+            "space" => {}
+            _ => bail!("line {} isn't 'pulse N' or 'space N'", number + 1),
+        }
+    }
+    finish(&mut name, &mut timings);
+    Ok(codes)
+}
+
+// where sent codes go
+#[derive(Debug, Clone)]
+pub enum Output {
+    Nowhere,
+    File(PathBuf),
+    Stdout(Vec<String>), // held until the terminal is given back
+}
+
+// the sending and learning side of a remote-control watch
+#[derive(Debug, Clone)]
+pub struct Remote {
+    output: Output,
+    toggle: bool,
+    pub sent: usize,                // codes sent this session
+    pub learned: Vec<Learned>,      // codes from the learn file
+    pub learn_index: usize,         // the one shown in learn mode
+    pub last_error: Option<String>, // why the last send couldn't be written
+}
+
+impl Remote {
+    pub fn new(output: Option<&Path>, learn: Option<&Path>) -> Result<Self> {
+        let output = match output {
+            Some(path) if path == Path::new("-") => Output::Stdout(Vec::new()),
+            Some(path) => Output::File(path.to_path_buf()),
+            None => Output::Nowhere,
+        };
+        let learned = match learn {
+            Some(path) => read_lirc(path)?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            output,
+            toggle: false,
+            sent: 0,
+            learned,
+            learn_index: 0,
+            last_error: None,
+        })
+    }
+
+    // writes the code in mode2 format, which ir-ctl and lirc's file driver can replay
+    pub fn send(&mut self, device: &str, code: &IrCode) {
+        self.toggle = !self.toggle;
+        self.sent += 1;
+        let mut text = format!("# {} {}\n", device, code.name);
+        for (index, duration) in code.timings(self.toggle).into_iter().enumerate() {
+            let kind = if index % 2 == 0 { "pulse" } else { "space" };
+            text.push_str(&format!("{} {}\n", kind, duration));
+        }
+
+        self.last_error = match &mut self.output {
+            Output::Nowhere => None,
+            Output::Stdout(lines) => {
+                lines.push(text);
+                None
+            }
+            Output::File(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&*path)
+                .and_then(|mut file| file.write_all(text.as_bytes()))
+                .err()
+                .map(|error| error.to_string()),
+        };
+    }
+
+    // codes meant for stdout, once the terminal is back to normal
+    pub fn take_stdout(&mut self) -> Vec<String> {
+        match &mut self.output {
+            Output::Stdout(lines) => std::mem::take(lines),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn current_learned(&self) -> Option<&Learned> {
+        self.learned.get(self.learn_index)
+    }
+
+    pub fn next_learned(&mut self) {
+        if !self.learned.is_empty() {
+            self.learn_index = (self.learn_index + 1) % self.learned.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nec_round_trip() {
+        // a one-byte address goes out with its inverse, a two-byte one as it is
+        for (address, command) in [(0x04, 0x08), (0x00, 0xFF), (0x1234, 0x55)] {
+            assert_eq!(
+                decode(&nec_timings(address, command)),
+                Some((Protocol::Nec, address, command))
+            );
+        }
+    }
+
+    #[test]
+    fn rc5_round_trip() {
+        for toggle in [false, true] {
+            for (address, command) in [(0x00, 12), (0x05, 0x3F), (0x1F, 0x45)] {
+                assert_eq!(
+                    decode(&rc5_timings(address, command, toggle)),
+                    Some((Protocol::Rc5, address, command)),
+                    "toggle {}",
+                    toggle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reads_lircd_raw_codes() {
+        // receivers stretch pulses a little, so the timings are written slightly off
+        let block = |timings: Vec<u32>| {
+            let words: Vec<String> = timings.iter().map(|t| (t + 40).to_string()).collect();
+            words
+                .chunks(6)
+                .map(|chunk| format!("          {}", chunk.join(" ")))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let conf = format!(
+            "begin remote
+  name  tv
+  flags RAW_CODES
+  gap 108000
+
+      begin raw_codes
+          name KEY_POWER
+{}
+
+          name KEY_VOLUMEUP # the big one
+{}
+      end raw_codes
+end remote
+",
+            block(nec_timings(0x04, 0x08)),
+            block(rc5_timings(0x00, 16, true)),
+        );
+        let path = std::env::temp_dir().join(format!("casiotoy-lircd-{}.conf", std::process::id()));
+        fs::write(&path, conf).unwrap();
+        let learned = read_lirc(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let codes: Vec<_> = learned.iter().map(|l| (l.name.as_str(), l.code)).collect();
+        assert_eq!(
+            codes,
+            [
+                ("KEY_POWER", Some((Protocol::Nec, 0x04, 0x08))),
+                ("KEY_VOLUMEUP", Some((Protocol::Rc5, 0x00, 16))),
+            ]
+        );
+    }
+}
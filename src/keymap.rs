@@ -0,0 +1,465 @@
+use anyhow::{Result, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::calculator::CalcKey;
+use crate::watch::{Module, WatchModel};
+
+// physical push buttons on the watch case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Button {
+    Mode,
+    StartStop,
+    Reset,
+    Light,
+    Alarm,
+    CrownPull,     // pull the crown out, or push it back in
+    CrownForward,  // one click of the crown
+    CrownBack,     // one click the other way
+    Calc(CalcKey), // keypad on calculator watches
+}
+
+impl Button {
+    pub const SIDE: [Button; 5] = [
+        Button::Mode,
+        Button::StartStop,
+        Button::Reset,
+        Button::Light,
+        Button::Alarm,
+    ];
+
+    pub const CROWN: [Button; 3] = [Button::CrownPull, Button::CrownForward, Button::CrownBack];
+
+    pub fn all() -> impl Iterator<Item = Button> {
+        Button::SIDE
+            .into_iter()
+            .chain(Button::CROWN)
+            .chain(CalcKey::ALL.into_iter().map(Button::Calc))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Button::Mode => "mode",
+            Button::StartStop => "start_stop",
+            Button::Reset => "reset",
+            Button::Light => "light",
+            Button::Alarm => "alarm",
+            Button::CrownPull => "crown_pull",
+            Button::CrownForward => "crown_forward",
+            Button::CrownBack => "crown_back",
+            Button::Calc(key) => key.name(),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Button::all().find(|button| button.name() == name)
+    }
+}
+
+// things the emulator itself does, as opposed to the watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppAction {
+    Quit,
+    Settings,
+    NextWatch, // moves the keys on to the next watch on the dashboard
+    Help,      // the operation manual overlay
+}
+
+impl AppAction {
+    pub const ALL: [AppAction; 4] = [
+        AppAction::Quit,
+        AppAction::Settings,
+        AppAction::NextWatch,
+        AppAction::Help,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AppAction::Quit => "quit",
+            AppAction::Settings => "settings",
+            AppAction::NextWatch => "next_watch",
+            AppAction::Help => "help",
+        }
+    }
+}
+
+// what a key does; serialized as "mode", "quit" or buttons pressed in turn such as "light+mode"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Action {
+    Press(Button),
+    Sequence(Vec<Button>),
+    App(AppAction),
+}
+
+impl TryFrom<String> for Action {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        let text = text.trim().to_lowercase();
+        if let Some(action) = AppAction::ALL.into_iter().find(|a| a.name() == text) {
+            return Ok(Action::App(action));
+        }
+
+        let buttons = text
+            .split('+')
+            .map(|name| {
+                Button::from_name(name.trim())
+                    .ok_or_else(|| anyhow!("unknown button or action '{}' in keymap", name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        match buttons.as_slice() {
+            [button] => Ok(Action::Press(*button)),
+            _ => Ok(Action::Sequence(buttons)),
+        }
+    }
+}
+
+impl From<Action> for String {
+    fn from(action: Action) -> Self {
+        match action {
+            Action::Press(button) => button.name().to_string(),
+            Action::Sequence(buttons) => buttons
+                .iter()
+                .map(|button| button.name())
+                .collect::<Vec<_>>()
+                .join("+"),
+            Action::App(action) => action.name().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    // accepts "m", "esc", "f5", "space", "ctrl+c", "alt+left" and so on
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // a lone "+" is the plus key, not a separator
+        let key = match parts.pop() {
+            Some("") if parts.last() == Some(&"") => {
+                parts.pop();
+                "+"
+            }
+            Some(key) => key,
+            None => bail!("empty key in keymap"),
+        };
+
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier '{}' in key '{}'", modifier, text),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            // terminals send shift+tab as a key of its own
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name if name.len() > 1 && name.starts_with('f') => match name[1..].parse() {
+                Ok(number @ 1..=12) => KeyCode::F(number),
+                _ => bail!("unknown key '{}' in keymap", text),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    // shift is part of the character itself, "shift+m" means "M"
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        modifiers -= KeyModifiers::SHIFT;
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => bail!("unknown key '{}' in keymap", text),
+                }
+            }
+        };
+
+        Ok(Self { code, modifiers })
+    }
+
+    fn matches(&self, key: &KeyEvent) -> bool {
+        // shift is already folded into the character for printable keys, and into backtab
+        let modifiers = match key.code {
+            KeyCode::Char(_) | KeyCode::BackTab => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        self.code == key.code && self.modifiers == modifiers
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_uppercase()),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    // the model's defaults with the user's overrides from the config on top
+    pub fn new(model: WatchModel, overrides: &BTreeMap<String, Action>) -> Result<Self> {
+        let mut keymap = Self::defaults(model);
+        for (key, action) in overrides {
+            keymap.bind(KeyBinding::parse(key)?, action.clone());
+        }
+        Ok(keymap)
+    }
+
+    fn defaults(model: WatchModel) -> Self {
+        let mut bindings = vec![
+            ("m", Action::Press(Button::Mode)),
+            ("s", Action::Press(Button::StartStop)),
+            ("r", Action::Press(Button::Reset)),
+            ("l", Action::Press(Button::Light)),
+            ("a", Action::Press(Button::Alarm)),
+            ("o", Action::App(AppAction::Settings)),
+            ("q", Action::App(AppAction::Quit)),
+            ("esc", Action::App(AppAction::Quit)),
+            ("tab", Action::App(AppAction::NextWatch)),
+            ("?", Action::App(AppAction::Help)),
+        ];
+
+        match model.module() {
+            Module::AE1200
+            | Module::DB36
+            | Module::PRG240
+            | Module::W800H
+            | Module::CMD40
+            | Module::VDB1000 => {}
+            // the crown is pulled with p and turned with the bracket keys
+            Module::AQ230 | Module::MQ24 | Module::Firmware => {
+                bindings.push(("p", Action::Press(Button::CrownPull)));
+                bindings.push(("]", Action::Press(Button::CrownForward)));
+                bindings.push(("[", Action::Press(Button::CrownBack)));
+            }
+            // the arrow keys steer and space starts the game
+            Module::GD8 => {
+                bindings.push(("left", Action::Press(Button::Reset)));
+                bindings.push(("right", Action::Press(Button::StartStop)));
+                bindings.push(("space", Action::Press(Button::Alarm)));
+            }
+            // a definition file can add its own keys for the case's buttons
+            Module::Custom => {
+                if let Some(definition) = model.definition() {
+                    for (key, action) in &definition.keymap {
+                        bindings.push((key, action.clone()));
+                    }
+                }
+            }
+            // the module 593 buttons are printed L, A and C on the case
+            Module::F91W => {
+                bindings.push(("c", Action::Press(Button::Mode)));
+            }
+            // g-shock buttons are A-D; A is left to the alarm key
+            Module::DW5600 | Module::GWM5610 | Module::GLX5600 => {
+                bindings.push(("c", Action::Press(Button::Mode)));
+                bindings.push(("d", Action::Press(Button::StartStop)));
+                bindings.push(("b", Action::Press(Button::Light)));
+            }
+            // the keypad sits on the number row and the usual operator keys
+            Module::CA53W => {
+                const DIGIT_KEYS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+                for (digit, key) in DIGIT_KEYS.into_iter().enumerate() {
+                    let calc_key = CalcKey::Digit(digit as u8);
+                    bindings.push((key, Action::Press(Button::Calc(calc_key))));
+                }
+                for (key, calc_key) in [
+                    (".", CalcKey::Point),
+                    ("+", CalcKey::Add),
+                    ("-", CalcKey::Subtract),
+                    ("*", CalcKey::Multiply),
+                    ("x", CalcKey::Multiply),
+                    ("/", CalcKey::Divide),
+                    ("=", CalcKey::Equals),
+                    ("enter", CalcKey::Equals),
+                ] {
+                    bindings.push((key, Action::Press(Button::Calc(calc_key))));
+                }
+            }
+        }
+
+        let mut keymap = Self {
+            bindings: Vec::new(),
+        };
+        for (key, action) in bindings {
+            let binding = KeyBinding::parse(key).expect("default keymap uses valid keys");
+            keymap.bind(binding, action);
+        }
+        keymap
+    }
+
+    fn bind(&mut self, binding: KeyBinding, action: Action) {
+        self.bindings.retain(|(existing, _)| *existing != binding);
+        self.bindings.push((binding, action));
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<&Action> {
+        self.bindings
+            .iter()
+            .find(|(binding, _)| binding.matches(key))
+            .map(|(_, action)| action)
+    }
+
+    pub fn key_for(&self, action: &Action) -> Option<KeyBinding> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| bound == action)
+            .map(|(binding, _)| *binding)
+    }
+
+    // builds hints like "press 'M' for mode, 'L' for backlight" from the active bindings
+    pub fn hint(&self, entries: &[(Button, &str)]) -> String {
+        let parts: Vec<String> = entries
+            .iter()
+            .filter_map(|(button, label)| {
+                self.key_for(&Action::Press(*button))
+                    .map(|key| format!("'{}' {}", key, label))
+            })
+            .collect();
+
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!("press {}", parts.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(code: KeyCode, modifiers: KeyModifiers) -> KeyBinding {
+        KeyBinding { code, modifiers }
+    }
+
+    fn parse(text: &str) -> KeyBinding {
+        KeyBinding::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_keys_and_modifiers() {
+        assert_eq!(parse("m"), binding(KeyCode::Char('m'), KeyModifiers::NONE));
+        assert_eq!(parse("Esc"), binding(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(
+            parse("space"),
+            binding(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("ctrl+c"),
+            binding(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("Control+Alt+left"),
+            binding(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(parse("shift+up"), binding(KeyCode::Up, KeyModifiers::SHIFT));
+    }
+
+    #[test]
+    fn plus_is_a_key_as_well_as_a_separator() {
+        assert_eq!(parse("+"), binding(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(
+            parse("ctrl++"),
+            binding(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert!(KeyBinding::parse("ctrl+").is_err());
+    }
+
+    #[test]
+    fn shift_folds_into_the_key() {
+        assert_eq!(
+            parse("shift+m"),
+            binding(KeyCode::Char('M'), KeyModifiers::NONE)
+        );
+        assert_eq!(parse("shift+m"), parse("M"));
+        assert_eq!(
+            parse("shift+tab"),
+            binding(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(parse("shift+tab"), parse("backtab"));
+    }
+
+    #[test]
+    fn function_keys() {
+        assert_eq!(parse("f1"), binding(KeyCode::F(1), KeyModifiers::NONE));
+        assert_eq!(parse("F12"), binding(KeyCode::F(12), KeyModifiers::NONE));
+        assert_eq!(parse("f"), binding(KeyCode::Char('f'), KeyModifiers::NONE));
+        for text in ["f0", "f13", "fx"] {
+            assert!(KeyBinding::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_rejected() {
+        for text in ["", "mm", "pgdn", "super+m", "ctrl+shift"] {
+            assert!(KeyBinding::parse(text).is_err(), "{}", text);
+        }
+        let error = KeyBinding::parse("hyper+m").unwrap_err().to_string();
+        assert_eq!(error, "unknown modifier 'hyper' in key 'hyper+m'");
+    }
+
+    #[test]
+    fn matches_ignores_the_shift_in_the_character() {
+        let upper = parse("M");
+        assert!(upper.matches(&KeyEvent::new(KeyCode::Char('M'), KeyModifiers::SHIFT)));
+        assert!(upper.matches(&KeyEvent::new(KeyCode::Char('M'), KeyModifiers::NONE)));
+        assert!(!upper.matches(&KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE)));
+        assert!(!upper.matches(&KeyEvent::new(KeyCode::Char('M'), KeyModifiers::CONTROL)));
+
+        let back = parse("shift+tab");
+        assert!(back.matches(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(!back.matches(&KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)));
+
+        // shift still counts on keys that aren't characters
+        let shift_up = parse("shift+up");
+        assert!(shift_up.matches(&KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT)));
+        assert!(!shift_up.matches(&KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)));
+    }
+}
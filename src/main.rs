@@ -1,26 +1,96 @@
 use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
 use crossterm::{
     ExecutableCommand,
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::prelude::*;
-use std::env;
 use std::io::stdout;
+use std::path::Path;
 
+mod analog;
+mod assembler;
+mod calculator;
+mod cli;
+mod databank;
+mod definition;
 mod display;
+mod gallery;
+mod game;
+mod ir;
+mod keymap;
+mod manual;
+mod mcu;
+mod menu;
+mod radio;
+mod script;
+mod sensors;
 mod settings;
+mod theme;
+mod tide;
 mod time;
+mod touch;
 mod watch;
 
-use watch::{Watch, WatchModel};
+use cli::{Cli, DashboardWatch};
+use gallery::{Choice, Gallery};
+use ir::Remote;
+use keymap::{Action, AppAction};
+use mcu::Mcu;
+use menu::SettingsMenu;
+use radio::{Receiver, SignalSource};
+use sensors::{SensorFeed, Sensors};
+use settings::WatchSettings;
+use time::TimeManager;
+use watch::{Module, Peripherals, Watch, WatchModel};
 
 fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let model = if args.len() > 1 && args[1] == "f91w" {
-        WatchModel::AE1200
+    let cli = Cli::parse();
+
+    if let Some(source) = &cli.assemble {
+        let output = assembler::assemble_file(source)?;
+        println!("wrote {}", output.display());
+        return Ok(());
+    }
+
+    // a broken definition stops a watch starting, but not the listing of the others
+    let definitions = definition::load(Path::new(definition::DEFINITION_DIR));
+    if cli.list_models {
+        for model in WatchModel::available() {
+            println!("{:<10}{}", model.name(), model.title());
+        }
+        if let Err(error) = definitions {
+            eprintln!("{:#}", error);
+        }
+        return Ok(());
+    }
+    definitions?;
+
+    // load everything before touching the terminal so errors stay readable
+    let start_time = cli.time.unwrap_or_else(chrono::Local::now);
+    let rom = match &cli.rom {
+        Some(path) => assembler::load(path)?,
+        None => assembler::assemble(assembler::DEMO_SOURCE)?,
+    };
+    let pick = cli.model.is_none() && cli.dashboard.is_empty();
+    let entries = match &cli.model {
+        _ if !cli.dashboard.is_empty() => cli.dashboard.clone(),
+        Some(model) => vec![DashboardWatch::only(model)],
+        None => Vec::new(),
+    };
+    let mut watches = entries
+        .iter()
+        .map(|entry| new_watch(&cli, entry, start_time, &rom))
+        .collect::<Result<Vec<_>>>()?;
+    let mut previews = if pick {
+        gallery_previews(&cli, start_time)?
     } else {
-        WatchModel::F91W
+        Vec::new()
     };
 
     stdout().execute(EnterAlternateScreen)?;
@@ -29,45 +99,249 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let mut watch = Watch::new(model)?;
-    run_app(&mut terminal, &mut watch)?;
+    let mut quit = false;
+    if pick {
+        let picked = run_gallery(&mut terminal, &mut previews).and_then(|index| {
+            index
+                .map(|index| pick_watch(&cli, &previews[index], &rom))
+                .transpose()
+        });
+        match picked {
+            Ok(Some(watch)) => watches = vec![watch],
+            Ok(None) => quit = true,
+            // back to the normal screen so the error can be read
+            Err(error) => {
+                disable_raw_mode()?;
+                stdout().execute(LeaveAlternateScreen)?;
+                return Err(error);
+            }
+        }
+    }
+
+    // only touch screens and picking a watch on the dashboard need the mouse, so a single
+    // button-only watch keeps the terminal's text selection
+    let mouse =
+        !quit && (watches.len() > 1 || watches.iter().any(|watch| watch.model.touch_screen()));
+    if mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+
+    if !quit {
+        run_app(&mut terminal, &mut watches)?;
+    }
 
     // clean up
+    if mouse {
+        stdout().execute(DisableMouseCapture)?;
+    }
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
 
+    // codes sent to stdout would have been lost under the watch face
+    for watch in &mut watches {
+        if let Some(remote) = watch.peripherals.remote_mut() {
+            for code in remote.take_stdout() {
+                print!("{}", code);
+            }
+        }
+    }
+
     Ok(())
 }
 
+// every watch gets its own clock, settings and inputs, so each one ticks on its own
+fn new_watch(
+    cli: &Cli,
+    entry: &DashboardWatch,
+    start_time: DateTime<Local>,
+    rom: &[u8],
+) -> Result<Watch> {
+    let model = cli::parse_model(&entry.model)?;
+    let profile = entry.profile.as_deref().unwrap_or(&cli.profile);
+    let settings = WatchSettings::load(&cli.config, profile)?;
+    let time_manager = TimeManager::starting_at(start_time + entry.offset, cli.speed);
+    // only the model that has the hardware opens its files
+    let peripherals = match model.module() {
+        Module::GWM5610 => Peripherals::Radio(Receiver::new(match &cli.signal {
+            Some(path) => SignalSource::from_file(path)?,
+            None => SignalSource::generated(cli.signal_noise),
+        })),
+        Module::PRG240 => Peripherals::Sensors(Sensors::new(
+            match &cli.sensors {
+                Some(path) => SensorFeed::from_file(path)?,
+                None => SensorFeed::Generated,
+            },
+            time_manager.current_time,
+        )),
+        Module::CMD40 => Peripherals::Remote(Remote::new(
+            cli.ir_output.as_deref(),
+            cli.ir_learn.as_deref(),
+        )?),
+        Module::Firmware => Peripherals::Mcu(Mcu::new(rom.to_vec())),
+        _ => Peripherals::None,
+    };
+    Watch::new(model, settings, time_manager, peripherals)
+}
+
+// the gallery runs every model so its previews are live, but without their peripherals: the
+// signal, sensor and ir files are only opened for the one picked. a model that can't even
+// preview is left out rather than keeping the rest from being picked
+fn gallery_previews(cli: &Cli, start_time: DateTime<Local>) -> Result<Vec<Watch>> {
+    let mut previews = Vec::new();
+    let mut failed = None;
+    for model in WatchModel::available() {
+        let preview = WatchSettings::load(&cli.config, &cli.profile).and_then(|settings| {
+            let time_manager = TimeManager::starting_at(start_time, cli.speed);
+            Watch::new(model, settings, time_manager, Peripherals::None)
+        });
+        match preview {
+            Ok(watch) => previews.push(watch),
+            Err(error) => failed = failed.or(Some(error)),
+        }
+    }
+    match failed {
+        Some(error) if previews.is_empty() => Err(error),
+        _ => Ok(previews),
+    }
+}
+
+// the real watch for the preview picked, carrying on from the time the preview shows
+fn pick_watch(cli: &Cli, preview: &Watch, rom: &[u8]) -> Result<Watch> {
+    let entry = DashboardWatch::only(preview.model.name());
+    let mut watch = new_watch(cli, &entry, preview.time_manager.current_time, rom)?;
+    watch.settings.default_model = Some(watch.model.name().to_string());
+    watch.settings.save()?;
+    Ok(watch)
+}
+
+// None if the gallery was left without picking a watch
+fn run_gallery(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    watches: &mut [Watch],
+) -> Result<Option<usize>> {
+    let models: Vec<WatchModel> = watches.iter().map(|watch| watch.model).collect();
+    let mut gallery = Gallery::new(&models, watches[0].settings.default_model.as_deref());
+
+    loop {
+        terminal.draw(|f| display::gallery_ui(f, watches, &gallery))?;
+
+        if event::poll(std::time::Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+        {
+            match gallery.handle_key(key, watches.len()) {
+                Choice::Picked(index) => return Ok(Some(index)),
+                Choice::Quit => return Ok(None),
+                Choice::Browsing => {}
+            }
+        }
+
+        // tick
+        for watch in watches.iter_mut() {
+            watch.update()?;
+        }
+    }
+}
+
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    watch: &mut Watch,
+    watches: &mut [Watch],
 ) -> Result<()> {
+    let mut menu = SettingsMenu::new();
+    let mut focus = 0;
+    let mut help = false;
+
     loop {
-        terminal.draw(|f| display::ui(f, watch))?;
+        terminal.draw(|f| display::ui(f, watches, focus, &menu, help))?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if handle_key_event(key, watch)? {
+            match event::read()? {
+                Event::Key(key)
+                    if handle_key_event(key, watches, &mut focus, &mut menu, &mut help)? =>
+                {
                     return Ok(());
                 }
+                // the menu and the manual both cover the lcd, so clicks can't reach its keys
+                Event::Mouse(mouse) if !menu.open && !help => {
+                    let size = terminal.size()?;
+                    let size = Rect::new(0, 0, size.width, size.height);
+                    handle_mouse_event(mouse, size, watches, &mut focus)?;
+                }
+                _ => {}
             }
         }
 
         // tick
-        watch.update()?;
+        for watch in watches.iter_mut() {
+            watch.update()?;
+        }
+    }
+}
+
+// a click on a watch focuses it, and a left click on the lcd is a touch on whichever key is under it
+fn handle_mouse_event(
+    mouse: MouseEvent,
+    size: Rect,
+    watches: &mut [Watch],
+    focus: &mut usize,
+) -> Result<()> {
+    if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+        return Ok(());
+    }
+    let position = Position::new(mouse.column, mouse.row);
+    let Some((index, cell)) = display::dashboard_cells(size, watches.len())
+        .into_iter()
+        .enumerate()
+        .find(|(_, cell)| cell.contains(position))
+    else {
+        return Ok(());
+    };
+    *focus = index;
+    let watch = &mut watches[index];
+    let lcd = display::lcd_area(cell, watch.model);
+    match touch::key_at(lcd, &watch.touch_keys(), mouse.column, mouse.row) {
+        Some(key) => watch.touch(key),
+        None => Ok(()),
     }
 }
 
-fn handle_key_event(key: KeyEvent, watch: &mut Watch) -> Result<bool> {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
-        KeyCode::Char('m') => watch.toggle_mode()?,
-        KeyCode::Char('s') => watch.toggle_start_stop()?,
-        KeyCode::Char('r') => watch.reset()?,
-        KeyCode::Char('l') => watch.toggle_light()?,
-        KeyCode::Char('a') => watch.set_alarm()?,
-        _ => {}
+fn handle_key_event(
+    key: KeyEvent,
+    watches: &mut [Watch],
+    focus: &mut usize,
+    menu: &mut SettingsMenu,
+    help: &mut bool,
+) -> Result<bool> {
+    let watch = &mut watches[*focus];
+    // the settings menu swallows all input while it is open, but for the key that opened it,
+    // which closes it again unless a value is being typed in
+    if menu.open {
+        let settings_key = matches!(
+            watch.keymap.action(&key),
+            Some(Action::App(AppAction::Settings))
+        );
+        if settings_key && menu.editor.is_none() {
+            menu.toggle(watch.model);
+            return Ok(false);
+        }
+        menu.handle_key(key, &mut watch.settings)?;
+        watch.apply_settings()?;
+        return Ok(false);
+    }
+
+    // the manual stays up while the buttons are pressed, and esc puts it away before it quits
+    if *help && key.code == KeyCode::Esc {
+        *help = false;
+        return Ok(false);
+    }
+
+    match watch.keymap.action(&key).cloned() {
+        Some(Action::App(AppAction::Quit)) => return Ok(true),
+        Some(Action::App(AppAction::Help)) => *help = !*help,
+        Some(Action::App(AppAction::Settings)) => menu.toggle(watch.model),
+        Some(Action::App(AppAction::NextWatch)) => *focus = (*focus + 1) % watches.len(),
+        Some(Action::Press(button)) => watch.press(button)?,
+        Some(Action::Sequence(buttons)) => watch.press_sequence(&buttons)?,
+        None => {}
     }
     Ok(false)
 }
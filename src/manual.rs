@@ -0,0 +1,92 @@
+use crate::keymap::Button;
+use crate::watch::{CA53WMode, ModeCycle, Module, Watch};
+
+// the help overlay's content for a watch as it is right now, read from the same control table the
+// buttons go through so it can't say something the watch won't do
+pub struct Manual {
+    pub modes: Vec<&'static str>, // in the order the mode button goes through them
+    pub current: Option<usize>,   // the one showing
+    pub controls: Vec<(Button, &'static str)>,
+    pub note: Option<&'static str>,
+}
+
+impl Manual {
+    pub fn new(watch: &Watch) -> Self {
+        let mut manual = match watch.model.module() {
+            Module::AE1200 => Self::cycle(watch.mode),
+            Module::F91W => Self::cycle(watch.f91w_mode),
+            Module::CA53W => Self::cycle(watch.ca53w_mode),
+            Module::DW5600 => Self::cycle(watch.dw5600_mode),
+            Module::GWM5610 => Self::cycle(watch.gwm5610_mode),
+            Module::DB36 => Self::cycle(watch.db36_mode),
+            Module::AQ230 => Self::cycle(watch.aq230_mode),
+            Module::PRG240 => Self::cycle(watch.prg240_mode),
+            Module::W800H => Self::cycle(watch.w800h_mode),
+            Module::GLX5600 => Self::cycle(watch.glx5600_mode),
+            Module::CMD40 => Self::cycle(watch.cmd40_mode),
+            Module::VDB1000 => Self::cycle(watch.vdb1000_mode),
+            Module::GD8 => Self::cycle(watch.gd8_mode),
+            Module::MQ24 | Module::Firmware => Self::without_modes(),
+            Module::Custom => Self::definition(watch),
+        };
+        manual.controls = watch
+            .controls()
+            .into_iter()
+            .map(|(button, control)| (button, control.label()))
+            .collect();
+
+        match watch.model.module() {
+            Module::CA53W if watch.ca53w_mode == CA53WMode::Calculator => {
+                manual.note = Some("the number and operator keys work the keypad");
+            }
+            Module::VDB1000 => manual.note = Some("everything else is on the touch screen"),
+            Module::Firmware => manual.note = Some("any modes are the rom's own"),
+            _ => {}
+        }
+
+        if watch.alarm_ringing() || watch.countdown_beep_start_time.is_some() {
+            manual.note = Some("any button silences the alarm first");
+        }
+        manual
+    }
+
+    fn cycle<M: ModeCycle>(mode: M) -> Self {
+        Self {
+            modes: M::ALL.iter().map(|mode| mode.name()).collect(),
+            current: M::ALL.iter().position(|other| *other == mode),
+            controls: Vec::new(),
+            note: None,
+        }
+    }
+
+    fn without_modes() -> Self {
+        Self {
+            modes: Vec::new(),
+            current: None,
+            controls: Vec::new(),
+            note: None,
+        }
+    }
+
+    // a definition's modes in the order the mode button follows its graph round
+    fn definition(watch: &Watch) -> Self {
+        let Some(definition) = watch.model.definition() else {
+            return Self::without_modes();
+        };
+        let mut order = vec![0];
+        let mut index = definition.next_mode(0);
+        while index != 0 && !order.contains(&index) {
+            order.push(index);
+            index = definition.next_mode(index);
+        }
+        Self {
+            modes: order
+                .iter()
+                .map(|&index| definition.modes[index].name.as_str())
+                .collect(),
+            current: order.iter().position(|&index| index == watch.custom_mode),
+            controls: Vec::new(),
+            note: None,
+        }
+    }
+}
@@ -0,0 +1,588 @@
+// the sharp sm510, the 4-bit lcd microcontroller in many casio modules and handheld lcd games: a
+// 4-bit accumulator, a B register pointing into 128 nibbles of ram, a two-level stack and a
+// program counter whose low six bits step as a shift register rather than counting. opcodes,
+// branching and timing follow the chip; which ram bit lights which segment is casiotoy's own glass.
+
+use crate::keymap::Button;
+
+// program memory, reached with PU (2 bits), PM (4 bits) and PL (6 bits)
+pub const ROM_SIZE: usize = 4096;
+pub const PAGE_SIZE: usize = 64;
+const RAM_SIZE: usize = 128;
+// instructions a second: a 32.768 kHz crystal, two clocks an instruction
+pub const CLOCK_HZ: u64 = 16384;
+// where the chip starts after reset, and after CEND once a second goes by or a key goes down
+pub const RESET_VECTOR: u16 = branch(3, 7, 0);
+pub const WAKE_VECTOR: u16 = branch(1, 0, 0);
+// TM looks its target up in the first page of rom, and always lands in PM 4
+pub const TM_PM: u16 = 4;
+// ram from here up is the lcd, one bit a segment
+const LCD_RAM: usize = 0x60;
+pub const SEGMENTS: usize = (RAM_SIZE - LCD_RAM) * 4;
+// the glass: eight digits of a b c d e f g dp, then the annunciators
+pub const DIGITS: usize = 8;
+pub const ANNUNCIATOR_SEGMENT: usize = DIGITS * 8;
+pub const ANNUNCIATORS: [&str; 8] = ["ALM", "SIG", "PM", "24H", "LAP", "ST", "TR", "DT"];
+// a key stays down this many instructions after a press, since the terminal never says when it's let go
+const KEY_HOLD_CYCLES: u32 = (CLOCK_HZ / 6) as u32;
+// the stack forgets the oldest return address after this many calls
+const STACK_DEPTH: usize = 2;
+// the 15-bit divider runs off the crystal, two counts an instruction, and sets 1S as it wraps
+const DIVIDER_MASK: u16 = 0x7fff;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+    None,
+    Bit,    // 0-3, added to the opcode
+    Bm,     // 0-3, added to the opcode and xored into Bm after the ram access
+    Nibble, // 0-15, added to the opcode
+    Short,  // a label in the same 64-byte page, its PL in the low six bits
+    Ram,    // a 7-bit ram address in a second byte
+    Long,   // a label in PM 0-11: PM in the opcode, PU and PL in a second byte
+    Call,   // a label in PM 0-3, the same way
+    Vector, // 0-63, the rom byte in the first page holding the target's PU and PL
+}
+
+pub struct Opcode {
+    pub mnemonic: &'static str,
+    pub base: u8,
+    pub operand: Operand,
+}
+
+const fn op(mnemonic: &'static str, base: u8, operand: Operand) -> Opcode {
+    Opcode {
+        mnemonic,
+        base,
+        operand,
+    }
+}
+
+// M is the ram nibble B points at; a skip passes over the next instruction, both bytes of a long one
+pub const OPCODES: &[Opcode] = &[
+    op("SKIP", 0x00, Operand::None),  // do nothing
+    op("ATBP", 0x01, Operand::None),  // lcd backplate on if A bit 0 is set
+    op("SBM", 0x02, Operand::None),   // the next instruction's ram access goes to Bm | 4
+    op("ATPL", 0x03, Operand::None),  // low four bits of PL = A
+    op("RM", 0x04, Operand::Bit),     // clear bit of M
+    op("ADD", 0x08, Operand::None),   // A += M
+    op("ADD11", 0x09, Operand::None), // A += M + C, C = carry, skip on carry
+    op("COMA", 0x0a, Operand::None),  // A = ~A
+    op("EXBLA", 0x0b, Operand::None), // swap A and Bl
+    op("SM", 0x0c, Operand::Bit),     // set bit of M
+    op("EXC", 0x10, Operand::Bm),     // swap A and M
+    op("EXCI", 0x14, Operand::Bm),    // swap A and M, Bl += 1, skip when Bl wraps to 0
+    op("LDA", 0x18, Operand::Bm),     // A = M
+    op("EXCD", 0x1c, Operand::Bm),    // swap A and M, Bl -= 1, skip when Bl wraps to 15
+    op("LAX", 0x20, Operand::Nibble), // A = x, unless the last instruction was a LAX too
+    op("ADX", 0x30, Operand::Nibble), // A += x, skip on carry except for ADX 10
+    op("LB", 0x40, Operand::Nibble),  // Bm low bits = x bits 0-1, Bl = x bits 2-3, 12-15 if set
+    op("TB", 0x51, Operand::None),    // skip if the beta input is high
+    op("TC", 0x52, Operand::None),    // skip if C is clear
+    op("TAM", 0x53, Operand::None),   // skip if A == M
+    op("TMI", 0x54, Operand::Bit),    // skip if bit of M is set
+    op("TIS", 0x58, Operand::None),   // skip if 1S is clear, then clear it
+    op("ATL", 0x59, Operand::None),   // A to the L segment outputs
+    op("TA0", 0x5a, Operand::None),   // skip if A == 0
+    op("TABL", 0x5b, Operand::None),  // skip if A == Bl
+    op("CEND", 0x5d, Operand::None),  // stop until 1S or a key, then carry on from WAKE_VECTOR
+    op("TAL", 0x5e, Operand::None),   // skip if the BA input is high
+    op("LBL", 0x5f, Operand::Ram),    // B = xy
+    op("ATFC", 0x60, Operand::None),  // A to the Y segment outputs
+    op("ATR", 0x61, Operand::None),   // R = A bits 0-1, the buzzer
+    op("WR", 0x62, Operand::None),    // shift a 0 into the strobe register
+    op("WS", 0x63, Operand::None),    // shift a 1 into the strobe register
+    op("INCB", 0x64, Operand::None),  // Bl += 1, skip when it wraps to 0
+    op("IDIV", 0x65, Operand::None),  // clear the divider
+    op("RC", 0x66, Operand::None),    // C = 0
+    op("SC", 0x67, Operand::None),    // C = 1
+    op("TF1", 0x68, Operand::None),   // skip if divider bit 14 is set
+    op("TF4", 0x69, Operand::None),   // skip if divider bit 11 is set
+    op("KTA", 0x6a, Operand::None),   // A = the K inputs on the strobed rows
+    op("ROT", 0x6b, Operand::None),   // rotate A right through C
+    op("DECB", 0x6c, Operand::None),  // Bl -= 1, skip when it wraps to 15
+    op("BDC", 0x6d, Operand::None),   // lcd blanked while C is set
+    op("RTN0", 0x6e, Operand::None),  // return
+    op("RTN1", 0x6f, Operand::None),  // return and skip
+    op("TL", 0x70, Operand::Long),    // jump anywhere in PM 0-11
+    op("TML", 0x7c, Operand::Call),   // call anywhere in PM 0-3
+    op("T", 0x80, Operand::Short),    // jump within the page
+    op("TM", 0xc0, Operand::Vector),  // call through the vector in rom byte x
+];
+
+// the K inputs on each strobe line; the light isn't wired to the chip on a real watch either
+pub const KEY_ROWS: [[Option<Button>; 4]; 2] = [
+    [
+        Some(Button::Mode),
+        Some(Button::StartStop),
+        Some(Button::Reset),
+        Some(Button::Alarm),
+    ],
+    [
+        Some(Button::CrownPull),
+        Some(Button::CrownForward),
+        Some(Button::CrownBack),
+        None,
+    ],
+];
+
+const fn branch(pu: u16, pm: u16, pl: u16) -> u16 {
+    (pu & 3) << 10 | (pm & 0xf) << 6 | (pl & 0x3f)
+}
+
+// PL steps as a 6-bit shift register fed with the xnor of its two low bits; it goes round the 63
+// other values of the page before coming back, and 0x3f leads only to itself
+pub fn next_pc(pc: u16) -> u16 {
+    let feed = if (pc >> 1 ^ pc) & 1 != 0 { 0 } else { 0x20 };
+    feed | (pc >> 1 & 0x1f) | (pc & !0x3f)
+}
+
+// whether the instruction takes a second byte
+pub fn long(opcode: u8) -> bool {
+    opcode == 0x5f || opcode & 0xf0 == 0x70
+}
+
+#[derive(Debug, Clone)]
+pub struct Mcu {
+    rom: Vec<u8>,
+    ram: [u8; RAM_SIZE],
+    pc: u16,
+    stack: [u16; STACK_DEPTH],
+    a: u8,
+    bm: u8,
+    bl: u8,
+    carry: bool,
+    skip: bool,
+    sbm: bool,       // armed by SBM for the instruction after it
+    high_page: bool, // SBM's Bm | 4, for the instruction running now
+    prev_op: u8,
+    strobe: u8,          // W, which drives the S lines that scan the keys
+    keys: [[u32; 4]; 2], // instructions left that each key is held for, by strobe row
+    divider: u16,
+    cycles: u64, // instruction cycles since reset
+    second: bool,
+    halted: bool,
+    backplate: bool, // BP, the lcd's common drive
+    blank: bool,     // BC, which blanks the lcd
+    pub buzzer: bool,
+}
+
+impl Mcu {
+    pub fn new(rom: Vec<u8>) -> Self {
+        let mut rom = rom;
+        rom.resize(ROM_SIZE, 0);
+        Self {
+            rom,
+            ram: [0; RAM_SIZE],
+            pc: RESET_VECTOR,
+            stack: [0; STACK_DEPTH],
+            a: 0,
+            bm: 0,
+            bl: 0,
+            carry: false,
+            skip: false,
+            sbm: false,
+            high_page: false,
+            prev_op: 0,
+            strobe: 0,
+            keys: [[0; 4]; 2],
+            divider: 0,
+            cycles: 0,
+            second: false,
+            halted: false,
+            backplate: false,
+            blank: false,
+            buzzer: false,
+        }
+    }
+
+    // holds the button's key line down for a moment; buttons the chip isn't wired to do nothing
+    pub fn press(&mut self, button: Button) {
+        for (row, keys) in KEY_ROWS.iter().enumerate() {
+            for (bit, key) in keys.iter().enumerate() {
+                if *key == Some(button) {
+                    self.keys[row][bit] = KEY_HOLD_CYCLES;
+                }
+            }
+        }
+    }
+
+    // segment n is bit n % 4 of lcd ram nibble n / 4; all dark while the lcd is off or blanked
+    pub fn segment(&self, index: usize) -> bool {
+        self.backplate
+            && !self.blank
+            && index < SEGMENTS
+            && self.ram[LCD_RAM + index / 4] & (1 << (index % 4)) != 0
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub fn run(&mut self, cycles: u64) {
+        let end = self.cycles + cycles;
+        while self.cycles < end {
+            let spent = if self.halted { 1 } else { self.step() };
+            for _ in 0..spent {
+                self.clock();
+            }
+        }
+    }
+
+    // the divider and the key hold times run whether or not the core is stopped
+    fn clock(&mut self) {
+        self.cycles += 1;
+        self.divider = (self.divider + 2) & DIVIDER_MASK;
+        if self.divider == 0 {
+            self.second = true;
+        }
+        for hold in self.keys.iter_mut().flatten() {
+            *hold = hold.saturating_sub(1);
+        }
+        if self.halted && (self.second || self.keys.iter().flatten().any(|hold| *hold > 0)) {
+            self.halted = false;
+            self.pc = WAKE_VECTOR;
+        }
+    }
+
+    fn fetch(&mut self) -> u8 {
+        let byte = self.rom[self.pc as usize];
+        self.pc = next_pc(self.pc);
+        byte
+    }
+
+    fn address(&self) -> usize {
+        let bm = if self.high_page { self.bm | 4 } else { self.bm };
+        ((bm as usize) << 4) | self.bl as usize
+    }
+
+    fn m(&self) -> u8 {
+        self.ram[self.address()]
+    }
+
+    fn set_m(&mut self, value: u8) {
+        self.ram[self.address()] = value & 0xf;
+    }
+
+    fn exchange(&mut self, bm: u8) {
+        let m = self.m();
+        self.set_m(self.a);
+        self.a = m;
+        self.bm ^= bm;
+    }
+
+    fn push(&mut self, address: u16) {
+        self.stack.rotate_right(1);
+        self.stack[0] = address;
+    }
+
+    fn pop(&mut self) -> u16 {
+        let address = self.stack[0];
+        self.stack.rotate_left(1);
+        address
+    }
+
+    fn inputs(&self) -> u8 {
+        let mut k = 0;
+        for (row, keys) in self.keys.iter().enumerate() {
+            if self.strobe & (1 << row) != 0 {
+                for (bit, hold) in keys.iter().enumerate() {
+                    if *hold > 0 {
+                        k |= 1 << bit;
+                    }
+                }
+            }
+        }
+        k
+    }
+
+    // runs one instruction and says how many cycles it took
+    fn step(&mut self) -> u64 {
+        let opcode = self.fetch();
+        let param = if long(opcode) { self.fetch() } else { 0 };
+        let mut cycles = if long(opcode) { 2 } else { 1 };
+        self.high_page = std::mem::take(&mut self.sbm);
+        if self.skip {
+            self.skip = false;
+            self.prev_op = 0;
+            return cycles;
+        }
+
+        let low2 = opcode & 0x3;
+        let low4 = opcode & 0xf;
+        match opcode {
+            0x01 => self.backplate = self.a & 1 != 0,
+            0x02 => self.sbm = true,
+            0x03 => self.pc = (self.pc & !0xf) | self.a as u16,
+            0x04..=0x07 => self.set_m(self.m() & !(1 << low2)),
+            0x08 => self.a = (self.a + self.m()) & 0xf,
+            0x09 => {
+                let sum = self.a + self.m() + self.carry as u8;
+                self.a = sum & 0xf;
+                self.carry = sum > 0xf;
+                self.skip = self.carry;
+            }
+            0x0a => self.a = !self.a & 0xf,
+            0x0b => std::mem::swap(&mut self.a, &mut self.bl),
+            0x0c..=0x0f => self.set_m(self.m() | (1 << low2)),
+            0x10..=0x13 => self.exchange(low2),
+            0x14..=0x17 => {
+                self.exchange(low2);
+                self.bl = (self.bl + 1) & 0xf;
+                self.skip = self.bl == 0;
+            }
+            0x18..=0x1b => {
+                self.a = self.m();
+                self.bm ^= low2;
+            }
+            0x1c..=0x1f => {
+                self.exchange(low2);
+                self.bl = self.bl.wrapping_sub(1) & 0xf;
+                self.skip = self.bl == 0xf;
+            }
+            // a run of LAX only loads the first, so a skip can pick between them
+            0x20..=0x2f if self.prev_op & 0xf0 != 0x20 => self.a = low4,
+            0x30..=0x3f => {
+                let sum = self.a + low4;
+                self.a = sum & 0xf;
+                self.skip = sum > 0xf && low4 != 10;
+            }
+            0x40..=0x4f => {
+                self.bm = (self.bm & 4) | low2;
+                self.bl = (low4 >> 2) | if low4 & 0xc != 0 { 0xc } else { 0 };
+            }
+            0x52 => self.skip = !self.carry,
+            0x53 => self.skip = self.a == self.m(),
+            0x54..=0x57 => self.skip = self.m() & (1 << low2) != 0,
+            0x58 => {
+                self.skip = !self.second;
+                self.second = false;
+            }
+            0x5a => self.skip = self.a == 0,
+            0x5b => self.skip = self.a == self.bl,
+            0x5d => self.halted = true,
+            0x5f => {
+                self.bm = param >> 4 & 0x7;
+                self.bl = param & 0xf;
+            }
+            0x61 => self.buzzer = self.a & 3 != 0,
+            0x62 => self.strobe <<= 1,
+            0x63 => self.strobe = (self.strobe << 1) | 1,
+            0x64 => {
+                self.bl = (self.bl + 1) & 0xf;
+                self.skip = self.bl == 0;
+            }
+            0x65 => self.divider = 0,
+            0x66 => self.carry = false,
+            0x67 => self.carry = true,
+            0x68 => self.skip = self.divider & 0x4000 != 0,
+            0x69 => self.skip = self.divider & 0x800 != 0,
+            0x6a => self.a = self.inputs(),
+            0x6b => {
+                let carry = self.a & 1 != 0;
+                self.a = (self.a >> 1) | (self.carry as u8) << 3;
+                self.carry = carry;
+            }
+            0x6c => {
+                self.bl = self.bl.wrapping_sub(1) & 0xf;
+                self.skip = self.bl == 0xf;
+            }
+            0x6d => self.blank = self.carry,
+            0x6e => self.pc = self.pop(),
+            0x6f => {
+                self.pc = self.pop();
+                self.skip = true;
+            }
+            0x70..=0x7b => {
+                self.pc = branch(param as u16 >> 6, low4 as u16, param as u16);
+            }
+            0x7c..=0x7f => {
+                self.push(self.pc);
+                self.pc = branch(param as u16 >> 6, low2 as u16, param as u16);
+            }
+            0x80..=0xbf => self.pc = (self.pc & !0x3f) | (opcode & 0x3f) as u16,
+            0xc0..=0xff => {
+                cycles += 1;
+                self.push(self.pc);
+                let vector = self.rom[(opcode & 0x3f) as usize] as u16;
+                self.pc = branch(vector >> 6, TM_PM, vector);
+            }
+            // TB and TAL read pins nothing drives, and ATL and ATFC feed segment outputs this
+            // glass doesn't have; anything else not in the table does nothing, like SKIP
+            _ => {}
+        }
+        self.prev_op = opcode;
+        cycles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler;
+
+    // assembles source placed at the reset vector
+    fn boot(source: &str) -> Mcu {
+        let rom = assembler::assemble(&format!(".org {}\n{}", RESET_VECTOR, source)).unwrap();
+        Mcu::new(rom)
+    }
+
+    fn steps(mcu: &mut Mcu, count: usize) {
+        for _ in 0..count {
+            mcu.step();
+        }
+    }
+
+    #[test]
+    fn program_counter_goes_round_the_page() {
+        let mut pc = 0x440;
+        let mut seen = Vec::new();
+        for _ in 0..63 {
+            seen.push(pc);
+            pc = next_pc(pc);
+            assert_eq!(pc & !0x3f, 0x440, "left the page");
+        }
+        assert_eq!(pc, 0x440);
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 63);
+        assert!(!seen.contains(&0x47f));
+        assert_eq!(next_pc(0x47f), 0x47f);
+        assert_eq!(next_pc(0x400), 0x420);
+    }
+
+    #[test]
+    fn arithmetic_sets_carry_and_skips() {
+        // 9 + 8 + C carries out, so ADD11 skips the LAX 1
+        let mut mcu = boot("LBL 0x10\n LAX 8\n EXC 0\n LAX 9\n SC\n ADD11\n LAX 1\n SKIP");
+        steps(&mut mcu, 7);
+        assert_eq!(mcu.a, 2);
+        assert!(mcu.carry);
+
+        // ADX skips on carry, except ADX 10 which never does
+        let mut mcu = boot("LAX 9\n ADX 10\n ADX 15\n LAX 7");
+        steps(&mut mcu, 4);
+        assert_eq!(mcu.a, 2);
+
+        // ROT goes right through C
+        let mut mcu = boot("LAX 0b0011\n SC\n ROT");
+        steps(&mut mcu, 3);
+        assert_eq!(mcu.a, 0b1001);
+        assert!(mcu.carry);
+    }
+
+    #[test]
+    fn only_the_first_of_a_run_of_lax_loads() {
+        let mut mcu = boot("LAX 5\n LAX 7\n SKIP\n LAX 7");
+        steps(&mut mcu, 4);
+        assert_eq!(mcu.a, 7);
+        let mut mcu = boot("LAX 5\n LAX 7");
+        steps(&mut mcu, 2);
+        assert_eq!(mcu.a, 5);
+    }
+
+    #[test]
+    fn ram_pointer_moves() {
+        // EXCI stores and steps Bl, skipping as it wraps; Bm ^= 1 on the way
+        let mut mcu = boot("LBL 0x1f\n LAX 4\n EXCI 1\n LAX 1\n LAX 2");
+        steps(&mut mcu, 4);
+        assert_eq!(mcu.ram[0x1f], 4);
+        assert_eq!((mcu.bm, mcu.bl), (0, 0));
+        assert_eq!(mcu.a, 0);
+
+        // LB packs Bm and a Bl of 0-3 or 12-15 into one byte
+        let mut mcu = boot("LBL 0x40\n LB 0b1110");
+        steps(&mut mcu, 2);
+        assert_eq!((mcu.bm, mcu.bl), (6, 0xf));
+
+        // SBM sends just the next access to Bm | 4
+        let mut mcu = boot("LBL 0x21\n LAX 3\n SBM\n EXC 0\n LAX 5\n EXC 0");
+        steps(&mut mcu, 6);
+        assert_eq!(mcu.ram[0x61], 3);
+        assert_eq!(mcu.ram[0x21], 5);
+    }
+
+    #[test]
+    fn a_skip_passes_over_both_bytes() {
+        let mut mcu = boot("RC\n TC\n LBL 0x7f\n LAX 1");
+        steps(&mut mcu, 4);
+        assert_eq!((mcu.bm, mcu.bl), (0, 0));
+        assert_eq!(mcu.a, 1);
+        // the skipped instruction still takes its two cycles
+        let mut mcu = boot("SC\n RC\n TC\n TL 0");
+        mcu.run(3);
+        assert_eq!(mcu.step(), 2);
+    }
+
+    #[test]
+    fn calls_return_past_the_whole_instruction() {
+        let source = "
+            TML sub
+            LAX 9
+            TM 0
+        .org 0x40
+        sub:
+            LAX 1
+            RTN1
+        .org 0x00
+            .vector vectored
+        .org 0x100
+        vectored:
+            LAX 2
+            RTN0
+        ";
+        let mut mcu = boot(source);
+        steps(&mut mcu, 3);
+        // RTN1 skipped the LAX 9
+        assert_eq!(mcu.a, 1);
+        steps(&mut mcu, 2);
+        assert_eq!(mcu.pc, branch(0, TM_PM, 0));
+        steps(&mut mcu, 2);
+        assert_eq!(mcu.a, 2);
+        assert_eq!(mcu.pc, next_pc(next_pc(next_pc(next_pc(RESET_VECTOR)))));
+    }
+
+    #[test]
+    fn cend_sleeps_until_a_second_or_a_key() {
+        let mut mcu = boot("CEND");
+        mcu.run(10);
+        assert!(mcu.halted);
+        mcu.press(Button::Reset);
+        mcu.run(1);
+        assert!(!mcu.halted);
+        assert_eq!(mcu.pc, WAKE_VECTOR);
+
+        // a second is 16384 instructions
+        let mut mcu = boot("CEND");
+        mcu.run(CLOCK_HZ - 1);
+        assert!(mcu.halted);
+        mcu.run(1);
+        assert!(mcu.second);
+        assert_eq!(mcu.pc, WAKE_VECTOR);
+    }
+
+    // the digit the demo has in ram at the given Bl of page 0
+    fn digits(mcu: &Mcu) -> [u8; 6] {
+        [4, 6, 8, 10, 12, 14].map(|bl| mcu.ram[bl])
+    }
+
+    #[test]
+    fn demo_rom_keeps_time() {
+        let mut mcu = Mcu::new(assembler::assemble(assembler::DEMO_SOURCE).unwrap());
+        mcu.run(CLOCK_HZ * 75 + CLOCK_HZ / 8);
+        assert_eq!(digits(&mcu), [0, 0, 0, 1, 1, 5]);
+        // "5" on the last digit: a c d f g
+        let lit: Vec<bool> = (0..8).map(|segment| mcu.segment(7 * 8 + segment)).collect();
+        assert_eq!(
+            lit,
+            [true, false, true, true, false, true, true, false],
+            "segments a-dp"
+        );
+
+        // start/stop is hours +1, reset zeroes the seconds and moves the minutes on
+        mcu.press(Button::StartStop);
+        mcu.run(CLOCK_HZ / 2);
+        mcu.press(Button::Reset);
+        mcu.run(CLOCK_HZ / 4);
+        assert_eq!(digits(&mcu), [0, 1, 0, 2, 0, 0]);
+    }
+}
@@ -0,0 +1,442 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::definition::Function;
+use crate::settings::{DailyAlarm, WatchSettings};
+use crate::theme::Theme;
+use crate::tide::PORTS;
+use crate::watch::{Module, WatchModel};
+
+// the in-app settings overlay, kept apart from the watch's own button-driven modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingField {
+    Profile,
+    TimeFormat24h,
+    DateFormatUs,
+    AutoLightDuration,
+    AlarmEnabled,
+    AlarmTime,
+    ExtraAlarm(usize), // index into the extra alarms
+    HourlySignal,
+    CountdownMinutes,
+    CountdownAutoRepeat,
+    DualTimeOffset,
+    TidePort,
+    Theme,
+}
+
+impl SettingField {
+    // the fields that mean something on this model, in menu order
+    pub fn available(model: WatchModel) -> Vec<SettingField> {
+        let functions = |function| {
+            model.definition().is_some_and(|definition| {
+                definition
+                    .modes
+                    .iter()
+                    .any(|mode| mode.function == function)
+            })
+        };
+        let module = model.module();
+        let countdown = matches!(module, Module::DW5600 | Module::GWM5610 | Module::W800H)
+            || functions(Function::Timer);
+        let dual_time = matches!(module, Module::CA53W | Module::AQ230 | Module::W800H)
+            || functions(Function::WorldTime);
+
+        let mut fields = vec![
+            SettingField::Profile,
+            SettingField::TimeFormat24h,
+            SettingField::DateFormatUs,
+            SettingField::AutoLightDuration,
+            SettingField::AlarmEnabled,
+            SettingField::AlarmTime,
+        ];
+        if module == Module::W800H {
+            fields.extend([SettingField::ExtraAlarm(0), SettingField::ExtraAlarm(1)]);
+        }
+        fields.push(SettingField::HourlySignal);
+        if countdown {
+            fields.extend([
+                SettingField::CountdownMinutes,
+                SettingField::CountdownAutoRepeat,
+            ]);
+        }
+        if dual_time {
+            fields.push(SettingField::DualTimeOffset);
+        }
+        if module == Module::GLX5600 {
+            fields.push(SettingField::TidePort);
+        }
+        fields.push(SettingField::Theme);
+        fields
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingField::Profile => "Profile",
+            SettingField::TimeFormat24h => "24-hour time",
+            SettingField::DateFormatUs => "Date format",
+            SettingField::AutoLightDuration => "Light duration",
+            SettingField::AlarmEnabled => "Alarm",
+            SettingField::AlarmTime => "Alarm time",
+            SettingField::ExtraAlarm(0) => "Alarm 2",
+            SettingField::ExtraAlarm(_) => "Alarm 3",
+            SettingField::HourlySignal => "Hourly signal",
+            SettingField::CountdownMinutes => "Countdown",
+            SettingField::CountdownAutoRepeat => "Auto-repeat",
+            SettingField::DualTimeOffset => "Dual time",
+            SettingField::TidePort => "Tide port",
+            SettingField::Theme => "Theme",
+        }
+    }
+
+    fn kind(self) -> FieldKind {
+        match self {
+            SettingField::TimeFormat24h
+            | SettingField::AlarmEnabled
+            | SettingField::HourlySignal
+            | SettingField::CountdownAutoRepeat => FieldKind::Toggle,
+            SettingField::Profile
+            | SettingField::DateFormatUs
+            | SettingField::TidePort
+            | SettingField::Theme => FieldKind::Choice,
+            SettingField::AutoLightDuration => FieldKind::Number { min: 1, max: 10 },
+            SettingField::CountdownMinutes => FieldKind::Number { min: 1, max: 60 },
+            SettingField::DualTimeOffset => FieldKind::Number { min: 0, max: 23 },
+            SettingField::AlarmTime | SettingField::ExtraAlarm(_) => FieldKind::Time,
+        }
+    }
+
+    // how a number field's value reads, in its own unit
+    fn number(self, value: u64) -> String {
+        match self {
+            SettingField::CountdownMinutes => format!("{} min", value),
+            SettingField::DualTimeOffset => format!("+{}H", value),
+            _ => format!("{}s", value),
+        }
+    }
+
+    // options and the index of the current value for choice fields
+    fn choices(self, settings: &WatchSettings) -> (Vec<String>, usize) {
+        match self {
+            SettingField::Profile => {
+                let options = settings
+                    .profiles()
+                    .unwrap_or_else(|_| vec![settings.profile.clone()]);
+                let index = options
+                    .iter()
+                    .position(|name| *name == settings.profile)
+                    .unwrap_or(0);
+                (options, index)
+            }
+            SettingField::DateFormatUs => (
+                vec!["MM/DD".to_string(), "DD/MM".to_string()],
+                if settings.date_format_us { 0 } else { 1 },
+            ),
+            SettingField::TidePort => {
+                let options: Vec<String> = PORTS.iter().map(|port| port.code.to_string()).collect();
+                let index = options
+                    .iter()
+                    .position(|code| *code == settings.tide_port)
+                    .unwrap_or(0);
+                (options, index)
+            }
+            SettingField::Theme => {
+                let options = Theme::available();
+                let index = options
+                    .iter()
+                    .position(|name| *name == settings.theme)
+                    .unwrap_or(0);
+                (options, index)
+            }
+            _ => (Vec::new(), 0),
+        }
+    }
+
+    pub fn value(self, settings: &WatchSettings) -> String {
+        match self {
+            SettingField::Profile => settings.profile.clone(),
+            SettingField::TimeFormat24h => on_off(settings.time_format_24h),
+            SettingField::DateFormatUs => {
+                if settings.date_format_us {
+                    "MM/DD".to_string()
+                } else {
+                    "DD/MM".to_string()
+                }
+            }
+            SettingField::AutoLightDuration => self.number(settings.auto_light_duration),
+            SettingField::AlarmEnabled => on_off(settings.alarm_enabled),
+            SettingField::AlarmTime => settings
+                .alarm_time
+                .clone()
+                .unwrap_or_else(|| "--:--".to_string()),
+            SettingField::ExtraAlarm(slot) => match settings.extra_alarms.get(slot) {
+                Some(DailyAlarm {
+                    enabled,
+                    time: Some(time),
+                }) => format!("{} {}", time, on_off(*enabled)),
+                _ => "--:--".to_string(),
+            },
+            SettingField::HourlySignal => on_off(settings.hourly_signal),
+            SettingField::CountdownMinutes => self.number(settings.countdown_minutes),
+            SettingField::CountdownAutoRepeat => on_off(settings.countdown_auto_repeat),
+            SettingField::DualTimeOffset => self.number(dual_time_hours(settings)),
+            SettingField::TidePort => settings.tide_port.clone(),
+            SettingField::Theme => settings.theme.clone(),
+        }
+    }
+}
+
+// the watches step dual time a whole hour at a time
+fn dual_time_hours(settings: &WatchSettings) -> u64 {
+    settings.dual_time_offset.rem_euclid(24 * 60) as u64 / 60
+}
+
+fn on_off(value: bool) -> String {
+    if value { "ON" } else { "OFF" }.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldKind {
+    Toggle,
+    Number { min: u64, max: u64 },
+    Time,
+    Choice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimePart {
+    Hour,
+    Minute,
+}
+
+// value being edited; only committed to the settings on enter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Editor {
+    Number(u64),
+    Time {
+        hour: u32,
+        minute: u32,
+        part: TimePart,
+    },
+    Choice {
+        options: Vec<String>,
+        index: usize,
+    },
+}
+
+impl Editor {
+    pub fn display(&self, field: SettingField) -> String {
+        match self {
+            Editor::Number(value) => format!("< {} >", field.number(*value)),
+            Editor::Time { hour, minute, part } => match part {
+                TimePart::Hour => format!("[{:02}]:{:02}", hour, minute),
+                TimePart::Minute => format!("{:02}:[{:02}]", hour, minute),
+            },
+            Editor::Choice { options, index } => format!("< {} >", options[*index]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SettingsMenu {
+    pub open: bool,
+    pub fields: Vec<SettingField>, // what the focused watch has, picked when the menu opens
+    pub selected: usize,
+    pub editor: Option<Editor>,
+    pub error: Option<String>, // why the last change was refused, until the next key
+}
+
+impl SettingsMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn toggle(&mut self, model: WatchModel) {
+        self.open = !self.open;
+        self.editor = None;
+        self.fields = SettingField::available(model);
+        self.selected = self.selected.min(self.fields.len() - 1);
+    }
+
+    pub fn selected_field(&self) -> SettingField {
+        self.fields[self.selected]
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, settings: &mut WatchSettings) -> Result<()> {
+        self.error = None;
+        if self.editor.is_some() {
+            return self.handle_editor_key(key, settings);
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                self.open = false;
+                self.editor = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self
+                    .selected
+                    .checked_sub(1)
+                    .unwrap_or(self.fields.len() - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1) % self.fields.len();
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.begin_edit(settings)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn begin_edit(&mut self, settings: &mut WatchSettings) -> Result<()> {
+        let field = self.selected_field();
+        self.editor = match field.kind() {
+            FieldKind::Toggle => {
+                // toggles have nothing to edit, flip them straight away
+                match field {
+                    SettingField::TimeFormat24h => {
+                        settings.time_format_24h = !settings.time_format_24h
+                    }
+                    SettingField::AlarmEnabled => settings.alarm_enabled = !settings.alarm_enabled,
+                    SettingField::HourlySignal => settings.hourly_signal = !settings.hourly_signal,
+                    SettingField::CountdownAutoRepeat => {
+                        settings.countdown_auto_repeat = !settings.countdown_auto_repeat
+                    }
+                    _ => {}
+                }
+                settings.save()?;
+                None
+            }
+            FieldKind::Number { .. } => Some(Editor::Number(match field {
+                SettingField::CountdownMinutes => settings.countdown_minutes,
+                SettingField::DualTimeOffset => dual_time_hours(settings),
+                _ => settings.auto_light_duration,
+            })),
+            FieldKind::Time => {
+                let time = match field {
+                    SettingField::ExtraAlarm(slot) => settings
+                        .extra_alarms
+                        .get(slot)
+                        .and_then(|alarm| alarm.time.as_deref()),
+                    _ => settings.alarm_time.as_deref(),
+                };
+                let (hour, minute) = time.and_then(parse_hh_mm).unwrap_or((0, 0));
+                Some(Editor::Time {
+                    hour,
+                    minute,
+                    part: TimePart::Hour,
+                })
+            }
+            FieldKind::Choice => {
+                let (options, index) = field.choices(settings);
+                Some(Editor::Choice { options, index })
+            }
+        };
+        Ok(())
+    }
+
+    fn handle_editor_key(&mut self, key: KeyEvent, settings: &mut WatchSettings) -> Result<()> {
+        let field = self.selected_field();
+        let Some(editor) = self.editor.as_mut() else {
+            return Ok(());
+        };
+
+        match key.code {
+            KeyCode::Esc => self.editor = None,
+            KeyCode::Enter => {
+                match editor {
+                    Editor::Number(value) => match field {
+                        SettingField::CountdownMinutes => settings.countdown_minutes = *value,
+                        SettingField::DualTimeOffset => {
+                            settings.dual_time_offset = *value as i64 * 60
+                        }
+                        _ => settings.auto_light_duration = *value,
+                    },
+                    Editor::Time { hour, minute, .. } => {
+                        let time = Some(format!("{:02}:{:02}", hour, minute));
+                        match field {
+                            // setting an extra alarm's time switches it on
+                            SettingField::ExtraAlarm(slot) => {
+                                if let Some(alarm) = settings.extra_alarms.get_mut(slot) {
+                                    alarm.time = time;
+                                    alarm.enabled = true;
+                                }
+                            }
+                            _ => settings.alarm_time = time,
+                        }
+                    }
+                    Editor::Choice { options, index } => match field {
+                        SettingField::Profile => {
+                            settings.switch_profile(&options[*index])?;
+                            if let Err(err) = Theme::load(&settings.theme) {
+                                self.error = Some(format!("{}, using default", err));
+                            }
+                        }
+                        // a broken theme file stays out of the settings
+                        SettingField::Theme => match Theme::load(&options[*index]) {
+                            Ok(_) => settings.theme = options[*index].clone(),
+                            Err(err) => {
+                                self.error = Some(err.to_string());
+                                return Ok(());
+                            }
+                        },
+                        SettingField::TidePort => settings.tide_port = options[*index].clone(),
+                        _ => settings.date_format_us = *index == 0,
+                    },
+                }
+                settings.save()?;
+                self.editor = None;
+            }
+            KeyCode::Backspace | KeyCode::Delete if field.kind() == FieldKind::Time => {
+                match field {
+                    SettingField::ExtraAlarm(slot) => {
+                        if let Some(alarm) = settings.extra_alarms.get_mut(slot) {
+                            *alarm = DailyAlarm::default();
+                        }
+                    }
+                    _ => settings.alarm_time = None,
+                }
+                settings.save()?;
+                self.editor = None;
+            }
+            KeyCode::Left | KeyCode::Char('h') => adjust(editor, field, -1, true),
+            KeyCode::Right | KeyCode::Char('l') => adjust(editor, field, 1, true),
+            KeyCode::Down | KeyCode::Char('j') => adjust(editor, field, -1, false),
+            KeyCode::Up | KeyCode::Char('k') => adjust(editor, field, 1, false),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// left/right move between the hour and minute of a time picker and step everything else
+fn adjust(editor: &mut Editor, field: SettingField, step: i64, horizontal: bool) {
+    match editor {
+        Editor::Number(value) => {
+            if let FieldKind::Number { min, max } = field.kind() {
+                *value = (*value as i64 + step).clamp(min as i64, max as i64) as u64;
+            }
+        }
+        Editor::Time { hour, minute, part } => {
+            if horizontal {
+                *part = match part {
+                    TimePart::Hour => TimePart::Minute,
+                    TimePart::Minute => TimePart::Hour,
+                };
+            } else {
+                match part {
+                    TimePart::Hour => *hour = (*hour as i64 + step).rem_euclid(24) as u32,
+                    TimePart::Minute => *minute = (*minute as i64 + step).rem_euclid(60) as u32,
+                }
+            }
+        }
+        Editor::Choice { options, index } => {
+            *index = (*index as i64 + step).rem_euclid(options.len() as i64) as usize;
+        }
+    }
+}
+
+fn parse_hh_mm(text: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = text.split_once(':')?;
+    Some((hour.parse().ok()?, minute.parse().ok()?))
+}
@@ -0,0 +1,412 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// the watch gives up if it hasn't locked on after this long
+const RECEPTION_TIMEOUT_SECS: i64 = 7 * 60;
+// signal strength is judged over the last few seconds of pulses
+const STRENGTH_WINDOW: usize = 10;
+pub const DEFAULT_NOISE: f64 = 0.02;
+
+// what the receiver makes of one second of signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symbol {
+    Zero,   // short pulse
+    One,    // long pulse
+    Marker, // no pulse in second 59, the next second starts a minute
+    Noise,  // couldn't tell
+}
+
+impl Symbol {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '0' => Some(Symbol::Zero),
+            '1' => Some(Symbol::One),
+            'M' | 'm' => Some(Symbol::Marker),
+            '?' => Some(Symbol::Noise),
+            _ => None,
+        }
+    }
+}
+
+// where the pulses come from instead of an antenna
+#[derive(Debug, Clone)]
+pub enum SignalSource {
+    // dcf77-style frames encoding the system clock, with some pulses garbled
+    Generated { noise: f64, seed: u64 },
+    // one symbol per second, played back from the start of each reception
+    Recorded(Vec<Symbol>),
+}
+
+impl SignalSource {
+    pub fn generated(noise: f64) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(1);
+        SignalSource::Generated {
+            noise,
+            seed: seed | 1,
+        }
+    }
+
+    // "0", "1", "M" for the minute marker and "?" for noise; whitespace and # comments are ignored
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("can't read signal file {}", path.display()))?;
+        let mut symbols = Vec::new();
+        for (number, line) in data.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            for c in line.chars().filter(|c| !c.is_whitespace()) {
+                match Symbol::from_char(c) {
+                    Some(symbol) => symbols.push(symbol),
+                    None => bail!(
+                        "invalid symbol '{}' on line {} of {}",
+                        c,
+                        number + 1,
+                        path.display()
+                    ),
+                }
+            }
+        }
+        Ok(SignalSource::Recorded(symbols))
+    }
+
+    // the symbol sent `index` seconds after the transmitter time `start`
+    fn symbol(&mut self, start: NaiveDateTime, index: usize) -> Symbol {
+        match self {
+            SignalSource::Generated { noise, seed } => {
+                let time = start + TimeDelta::seconds(index as i64);
+                let second = time.second() as usize;
+                let symbol = if second == 59 {
+                    Symbol::Marker
+                } else {
+                    // each minute's frame announces the minute that follows it
+                    let next_minute = time.with_second(0).unwrap_or(time) + TimeDelta::minutes(1);
+                    if encode(next_minute)[second] {
+                        Symbol::One
+                    } else {
+                        Symbol::Zero
+                    }
+                };
+
+                let roll = next_random(seed);
+                if roll < *noise {
+                    Symbol::Noise
+                } else if roll < *noise * 1.25 {
+                    // now and then a pulse is misread rather than lost
+                    match symbol {
+                        Symbol::Zero => Symbol::One,
+                        Symbol::One => Symbol::Zero,
+                        other => other,
+                    }
+                } else {
+                    symbol
+                }
+            }
+            // after the recording runs out there's nothing but static
+            SignalSource::Recorded(symbols) => symbols.get(index).copied().unwrap_or(Symbol::Noise),
+        }
+    }
+}
+
+// xorshift, good enough to garble pulses
+fn next_random(state: &mut u64) -> f64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Debug, Clone)]
+struct Session {
+    started: DateTime<Local>,   // watch time when reception started
+    transmitter: NaiveDateTime, // transmitter time at that moment
+    received: usize,            // seconds of signal taken in so far
+    frame: Option<Vec<Symbol>>, // symbols since the last marker, None until synced
+    recent: VecDeque<bool>,     // whether each recent second was readable
+    previous: Option<(NaiveDateTime, usize)>, // last decoded frame and when it ended
+}
+
+// the radio part of a wave ceptor watch
+#[derive(Debug, Clone)]
+pub struct Receiver {
+    source: SignalSource,
+    session: Option<Session>,
+    pub last_success: Option<DateTime<Local>>, // corrected watch time of the last good reception
+    pub last_failed: bool,                     // the most recent attempt gave up
+}
+
+impl Receiver {
+    pub fn new(source: SignalSource) -> Self {
+        Self {
+            source,
+            session: None,
+            last_success: None,
+            last_failed: false,
+        }
+    }
+
+    pub fn start(&mut self, now: DateTime<Local>) {
+        // a generated signal carries the real time, which is what we're setting the watch to
+        let transmitter = Local::now().naive_local();
+        let transmitter = transmitter.with_nanosecond(0).unwrap_or(transmitter);
+        self.session = Some(Session {
+            started: now,
+            transmitter,
+            received: 0,
+            frame: None,
+            recent: VecDeque::with_capacity(STRENGTH_WINDOW),
+            previous: None,
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.session = None;
+    }
+
+    pub fn receiving(&self) -> bool {
+        self.session.is_some()
+    }
+
+    // 0 to 3, like the L1-L3 bars shown while receiving
+    pub fn level(&self) -> u8 {
+        let Some(session) = &self.session else {
+            return 0;
+        };
+        if session.recent.is_empty() {
+            return 0;
+        }
+        let readable = session.recent.iter().filter(|&&ok| ok).count();
+        match readable * 10 / session.recent.len() {
+            10 => 3,
+            8..=9 => 2,
+            5..=7 => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn received_on(&self, date: NaiveDate) -> bool {
+        self.last_success
+            .is_some_and(|time| time.date_naive() == date)
+    }
+
+    // takes in the seconds of signal since the last call; returns the time to set once
+    // two frames agree with each other
+    pub fn update(&mut self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let elapsed = (now - self.session.as_ref()?.started).num_seconds();
+        if !(0..RECEPTION_TIMEOUT_SECS).contains(&elapsed) {
+            self.session = None;
+            self.last_failed = true;
+            return None;
+        }
+        let session = self.session.as_mut()?;
+
+        while session.received < elapsed as usize {
+            let symbol = self.source.symbol(session.transmitter, session.received);
+            session.received += 1;
+
+            if session.recent.len() == STRENGTH_WINDOW {
+                session.recent.pop_front();
+            }
+            session.recent.push_back(symbol != Symbol::Noise);
+
+            if symbol != Symbol::Marker {
+                if let Some(frame) = &mut session.frame {
+                    frame.push(symbol);
+                }
+                continue;
+            }
+
+            // the marker ends a frame, and the second after it is the start of the minute
+            let decoded = session.frame.take().and_then(|frame| decode(&frame));
+            session.frame = Some(Vec::with_capacity(59));
+            let Some(minute) = decoded else {
+                continue;
+            };
+            let confirmed = session.previous.is_some_and(|(previous, at)| {
+                // the two frames have to be as far apart as the seconds between them
+                previous + TimeDelta::seconds((session.received - at) as i64) == minute
+            });
+            session.previous = Some((minute, session.received));
+            if !confirmed {
+                continue;
+            }
+
+            // we're at the end of second 59, so the watch should read the new minute
+            let late = TimeDelta::milliseconds(
+                (now - session.started).num_milliseconds() - session.received as i64 * 1000,
+            );
+            let time = Local.from_local_datetime(&minute).earliest()? + late;
+            self.session = None;
+            self.last_success = Some(time);
+            self.last_failed = false;
+            return Some(time);
+        }
+        None
+    }
+}
+
+// weights of each bit in a binary-coded decimal field
+const MINUTE_BITS: [u32; 7] = [1, 2, 4, 8, 10, 20, 40];
+const HOUR_BITS: [u32; 6] = [1, 2, 4, 8, 10, 20];
+const DAY_BITS: [u32; 6] = [1, 2, 4, 8, 10, 20];
+const WEEKDAY_BITS: [u32; 3] = [1, 2, 4];
+const MONTH_BITS: [u32; 5] = [1, 2, 4, 8, 10];
+const YEAR_BITS: [u32; 8] = [1, 2, 4, 8, 10, 20, 40, 80];
+
+// dcf77 layout: bit 20 starts the time, then minute, hour and date each with parity
+fn encode(time: NaiveDateTime) -> [bool; 59] {
+    let mut bits = [false; 59];
+    bits[18] = true; // standard time
+    bits[20] = true;
+    put_bcd(&mut bits[21..28], &MINUTE_BITS, time.minute());
+    put_bcd(&mut bits[29..35], &HOUR_BITS, time.hour());
+    put_bcd(&mut bits[36..42], &DAY_BITS, time.day());
+    put_bcd(
+        &mut bits[42..45],
+        &WEEKDAY_BITS,
+        time.weekday().number_from_monday(),
+    );
+    put_bcd(&mut bits[45..50], &MONTH_BITS, time.month());
+    put_bcd(
+        &mut bits[50..58],
+        &YEAR_BITS,
+        time.year().rem_euclid(100) as u32,
+    );
+    bits[28] = odd(&bits[21..28]);
+    bits[35] = odd(&bits[29..35]);
+    bits[58] = odd(&bits[36..58]);
+    bits
+}
+
+// None unless the frame is complete and every check passes
+fn decode(frame: &[Symbol]) -> Option<NaiveDateTime> {
+    if frame.len() != 59 {
+        return None;
+    }
+    let bits = frame
+        .iter()
+        .map(|symbol| match symbol {
+            Symbol::Zero => Some(false),
+            Symbol::One => Some(true),
+            Symbol::Marker | Symbol::Noise => None,
+        })
+        .collect::<Option<Vec<bool>>>()?;
+
+    if bits[0] || !bits[20] {
+        return None;
+    }
+    if odd(&bits[21..28]) != bits[28] || odd(&bits[29..35]) != bits[35] {
+        return None;
+    }
+    if odd(&bits[36..58]) != bits[58] {
+        return None;
+    }
+
+    let minute = get_bcd(&bits[21..28], &MINUTE_BITS)?;
+    let hour = get_bcd(&bits[29..35], &HOUR_BITS)?;
+    let day = get_bcd(&bits[36..42], &DAY_BITS)?;
+    let month = get_bcd(&bits[45..50], &MONTH_BITS)?;
+    let year = get_bcd(&bits[50..58], &YEAR_BITS)?;
+    NaiveDate::from_ymd_opt(2000 + year as i32, month, day)?.and_hms_opt(hour, minute, 0)
+}
+
+fn put_bcd(bits: &mut [bool], weights: &[u32], value: u32) {
+    for (bit, &weight) in bits.iter_mut().zip(weights) {
+        *bit = if weight < 10 {
+            (value % 10) & weight != 0
+        } else {
+            (value / 10) & (weight / 10) != 0
+        };
+    }
+}
+
+fn get_bcd(bits: &[bool], weights: &[u32]) -> Option<u32> {
+    let set = || {
+        bits.iter()
+            .zip(weights)
+            .filter(|(bit, _)| **bit)
+            .map(|(_, weight)| weight)
+    };
+    let units: u32 = set().filter(|&&weight| weight < 10).sum();
+    // a units digit over 9 can't be a real time
+    if units > 9 {
+        return None;
+    }
+    Some(set().sum())
+}
+
+// even parity: the parity bit makes the count of ones even
+fn odd(bits: &[bool]) -> bool {
+    bits.iter().filter(|&&bit| bit).count() % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn frame(time: NaiveDateTime) -> Vec<Symbol> {
+        encode(time)
+            .iter()
+            .map(|&bit| if bit { Symbol::One } else { Symbol::Zero })
+            .collect()
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for text in ["2026-10-18 07:05", "2031-12-31 23:59", "2000-01-01 00:00"] {
+            assert_eq!(decode(&frame(at(text))), Some(at(text)));
+        }
+    }
+
+    #[test]
+    fn parity_failure_is_rejected() {
+        let time = at("2026-10-18 07:05");
+        // a flipped minute bit, hour bit or date bit each break their own parity
+        for bit in [21, 30, 40] {
+            let mut symbols = frame(time);
+            symbols[bit] = match symbols[bit] {
+                Symbol::One => Symbol::Zero,
+                _ => Symbol::One,
+            };
+            assert_eq!(decode(&symbols), None, "bit {}", bit);
+        }
+        assert_eq!(decode(&frame(time)[..58]), None);
+    }
+
+    #[test]
+    fn noisy_frame_waits_for_confirmation() {
+        let first = at("2026-10-18 07:05");
+        let mut noisy = frame(first + TimeDelta::minutes(1));
+        noisy[30] = Symbol::Noise;
+        let mut symbols = vec![Symbol::Marker];
+        symbols.extend(frame(first));
+        symbols.push(Symbol::Marker);
+        symbols.extend(noisy);
+        symbols.push(Symbol::Marker);
+        symbols.extend(frame(first + TimeDelta::minutes(2)));
+        symbols.push(Symbol::Marker);
+
+        let started = Local.with_ymd_and_hms(2026, 10, 18, 6, 0, 0).unwrap();
+        let mut receiver = Receiver::new(SignalSource::Recorded(symbols));
+        receiver.start(started);
+        // one good frame isn't trusted on its own, and a noisy one doesn't count
+        assert_eq!(receiver.update(started + TimeDelta::seconds(61)), None);
+        assert_eq!(receiver.update(started + TimeDelta::seconds(121)), None);
+        assert!(receiver.receiving());
+        let set = receiver.update(started + TimeDelta::seconds(181));
+        let expected = Local
+            .from_local_datetime(&(first + TimeDelta::minutes(2)))
+            .unwrap();
+        assert_eq!(set, Some(expected));
+        assert!(!receiver.receiving());
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Local, Timelike};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, FuncArgs, Map, Scope};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+// a call gets this many operations before it's stopped, so a runaway loop can't hang the watch
+const MAX_OPERATIONS: u64 = 50_000;
+
+// what a script can put on a row of the lcd
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),   // left-aligned
+    Centre(String), // centred
+    Digits(String), // big seven-segment digits, three rows tall
+}
+
+// filled in by the functions the script calls while it runs
+#[derive(Debug, Default)]
+struct Output {
+    rows: Vec<(usize, Segment)>,
+    beep: bool,
+    message: Option<String>,
+}
+
+// a mode written in rhai: optional init(), button(name), tick(now) and draw(now) functions that
+// share their state through `this`, with nothing but the lcd, beep() and print() to talk to
+pub struct Script {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    state: Dynamic,
+    output: Rc<RefCell<Output>>,
+    pub rows: Vec<(usize, Segment)>, // what draw() put on the lcd last time
+    pub status: Option<String>,      // the last error or print(), for the status line
+}
+
+// an engine without file or network access and with limits on everything a script can grow
+fn sandbox() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(1024);
+    engine.set_max_map_size(256);
+    engine.disable_symbol("eval");
+    // the default resolver would read any .rhai file on disk through `import`
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine
+}
+
+// checks a script parses, without running any of it
+pub fn compile(path: &Path) -> Result<()> {
+    sandbox()
+        .compile_file(path.to_path_buf())
+        .map(|_| ())
+        .map_err(|error| anyhow!("{}", error))
+}
+
+impl Script {
+    pub fn load(path: &Path, now: DateTime<Local>) -> Result<Self> {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let mut engine = sandbox();
+
+        let rows = output.clone();
+        engine.register_fn("text", move |row: i64, text: &str| {
+            let segment = Segment::Text(text.to_string());
+            rows.borrow_mut().rows.push((row.max(0) as usize, segment));
+        });
+        let rows = output.clone();
+        engine.register_fn("centre", move |row: i64, text: &str| {
+            let segment = Segment::Centre(text.to_string());
+            rows.borrow_mut().rows.push((row.max(0) as usize, segment));
+        });
+        let rows = output.clone();
+        engine.register_fn("digits", move |row: i64, text: &str| {
+            let segment = Segment::Digits(text.to_string());
+            rows.borrow_mut().rows.push((row.max(0) as usize, segment));
+        });
+        let beep = output.clone();
+        engine.register_fn("beep", move || beep.borrow_mut().beep = true);
+        // print would scribble over the terminal, so it goes to the status line instead
+        let message = output.clone();
+        engine.on_print(move |text| message.borrow_mut().message = Some(text.to_string()));
+        engine.on_debug(|_, _, _| {});
+
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|error| anyhow!("{}", error))?;
+        let mut script = Self {
+            name: path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            engine,
+            ast,
+            state: Dynamic::from_map(Map::new()),
+            output,
+            rows: Vec::new(),
+            status: None,
+        };
+        script.call("init", ());
+        script.draw(now);
+        Ok(script)
+    }
+
+    // start_stop, reset or alarm; mode and light stay with the watch
+    pub fn button(&mut self, name: &str, now: DateTime<Local>) {
+        if self.call("button", (name.to_string(),)) {
+            self.status = None;
+        }
+        self.draw(now);
+    }
+
+    pub fn tick(&mut self, now: DateTime<Local>) {
+        self.call("tick", (time_map(now),));
+    }
+
+    pub fn draw(&mut self, now: DateTime<Local>) {
+        self.output.borrow_mut().rows.clear();
+        if self.call("draw", (time_map(now),)) {
+            self.rows = std::mem::take(&mut self.output.borrow_mut().rows);
+        }
+    }
+
+    // true once for each beep() since the last time
+    pub fn take_beep(&mut self) -> bool {
+        std::mem::take(&mut self.output.borrow_mut().beep)
+    }
+
+    // false if the function failed; a script doesn't have to define any of them
+    fn call(&mut self, function: &str, args: impl FuncArgs) -> bool {
+        if !self.ast.iter_functions().any(|f| f.name == function) {
+            return true;
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            function,
+            args,
+        );
+        if let Some(message) = self.output.borrow_mut().message.take() {
+            self.status = Some(message);
+        }
+        match result {
+            Ok(_) => true,
+            Err(error) => {
+                self.status = Some(format!("{} {}(): {}", self.name, function, error));
+                false
+            }
+        }
+    }
+}
+
+// what scripts get as `now`
+fn time_map(time: DateTime<Local>) -> Map {
+    let mut map = Map::new();
+    let fields = [
+        ("year", time.year() as i64),
+        ("month", time.month() as i64),
+        ("day", time.day() as i64),
+        ("weekday", time.weekday().num_days_from_monday() as i64),
+        ("hour", time.hour() as i64),
+        ("minute", time.minute() as i64),
+        ("second", time.second() as i64),
+        ("millis", time.timestamp_subsec_millis() as i64),
+        ("timestamp", time.timestamp()),
+    ];
+    for (name, value) in fields {
+        map.insert(name.into(), value.into());
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn import_finds_nothing() {
+        let dir = std::env::temp_dir().join(format!("casiotoy-import-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("helper.rhai"), "export const ANSWER = 42;").unwrap();
+        let path = dir.join("mode.rhai");
+        fs::write(
+            &path,
+            r#"fn init() { import "helper" as helper; this.answer = helper::ANSWER; }
+               fn draw(now) { text(0, `${this.answer}`); }"#,
+        )
+        .unwrap();
+
+        let script = Script::load(&path, Local::now()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let status = script.status.expect("init() should have failed");
+        assert!(status.contains("init()"), "{}", status);
+        assert!(status.contains("helper"), "{}", status);
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// the pressure graph gets a new bar this often, and shows this many
+const PRESSURE_SAMPLE_MINUTES: i64 = 60;
+pub const PRESSURE_SAMPLES: usize = 12;
+const SEA_LEVEL_HPA: f64 = 1013.25;
+
+// what the three sensors report at one moment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    #[serde(default)]
+    pub seconds: f64, // since the start of the trace
+    pub pressure: f64,    // hPa
+    pub temperature: f64, // °C
+    pub heading: f64,     // degrees clockwise from north
+}
+
+// offsets set with the watch buttons, saved with the settings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Calibration {
+    pub heading: f64,     // degrees, e.g. for magnetic declination
+    pub pressure: f64,    // hPa
+    pub altitude: f64,    // metres
+    pub temperature: f64, // °C
+}
+
+// where readings come from instead of real sensors
+#[derive(Debug, Clone)]
+pub enum SensorFeed {
+    // a day out in the hills: climbing and descending with the weather slowly turning
+    Generated,
+    // recorded readings, replayed against watch time and held at the last one
+    Trace(Vec<Reading>),
+}
+
+impl SensorFeed {
+    // csv with seconds,pressure,temperature,heading columns, or a json array of readings
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("can't read sensor trace {}", path.display()))?;
+        let mut readings: Vec<Reading> = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&data)
+                .with_context(|| format!("invalid sensor trace {}", path.display()))?
+        } else {
+            parse_csv(&data).with_context(|| format!("invalid sensor trace {}", path.display()))?
+        };
+        if readings.is_empty() {
+            bail!("sensor trace {} has no readings", path.display());
+        }
+        readings.sort_by(|a, b| a.seconds.total_cmp(&b.seconds));
+        Ok(SensorFeed::Trace(readings))
+    }
+
+    fn reading(&self, seconds: f64) -> Reading {
+        match self {
+            SensorFeed::Generated => {
+                let altitude = 900.0 + 500.0 * (seconds / 5400.0 * PI).sin();
+                let sea_level = SEA_LEVEL_HPA - 8.0 * (seconds / (12.0 * 3600.0) * PI).sin();
+                Reading {
+                    seconds,
+                    pressure: pressure_at(altitude, sea_level),
+                    temperature: 16.0 + 3.0 * (seconds / 3600.0 * PI).sin() - altitude * 0.0065,
+                    heading: (60.0 + 80.0 * (seconds / 600.0 * PI).sin()).rem_euclid(360.0),
+                }
+            }
+            SensorFeed::Trace(readings) => {
+                let after = readings.partition_point(|reading| reading.seconds <= seconds);
+                match (
+                    after.checked_sub(1).map(|index| readings[index]),
+                    readings.get(after),
+                ) {
+                    (Some(before), Some(next)) => {
+                        let t = (seconds - before.seconds) / (next.seconds - before.seconds);
+                        let lerp = |a: f64, b: f64| a + (b - a) * t;
+                        // turn the short way round, not through south from 350° to 10°
+                        let turn =
+                            (next.heading - before.heading + 540.0).rem_euclid(360.0) - 180.0;
+                        Reading {
+                            seconds,
+                            pressure: lerp(before.pressure, next.pressure),
+                            temperature: lerp(before.temperature, next.temperature),
+                            heading: (before.heading + turn * t).rem_euclid(360.0),
+                        }
+                    }
+                    (Some(last), None) => last,
+                    (None, _) => readings[0],
+                }
+            }
+        }
+    }
+}
+
+fn parse_csv(data: &str) -> Result<Vec<Reading>> {
+    let mut readings = Vec::new();
+    for (number, line) in data.lines().enumerate() {
+        let line = line.trim();
+        // skip blank lines and a header row
+        if line.is_empty() || line.starts_with(|c: char| c.is_alphabetic() || c == '#') {
+            continue;
+        }
+        let values = line
+            .split(',')
+            .map(|value| value.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("line {} has a value that isn't a number", number + 1))?;
+        let [seconds, pressure, temperature, heading] = values[..] else {
+            bail!(
+                "line {} should have 4 values, not {}",
+                number + 1,
+                values.len()
+            );
+        };
+        readings.push(Reading {
+            seconds,
+            pressure,
+            temperature,
+            heading,
+        });
+    }
+    Ok(readings)
+}
+
+// international standard atmosphere, close enough for a wrist altimeter
+fn pressure_at(altitude: f64, sea_level: f64) -> f64 {
+    sea_level * (1.0 - altitude / 44330.0).powf(5.255)
+}
+
+fn altitude_at(pressure: f64) -> f64 {
+    44330.0 * (1.0 - (pressure / SEA_LEVEL_HPA).powf(1.0 / 5.255))
+}
+
+// the sensor side of a triple-sensor watch
+#[derive(Debug, Clone)]
+pub struct Sensors {
+    feed: SensorFeed,
+    started: DateTime<Local>, // watch time when the feed started
+    raw: Reading,
+    history: VecDeque<f64>, // calibrated pressure samples, oldest first
+    last_sample: Option<DateTime<Local>>,
+    pub bearing_memory: Option<f64>, // heading marked with the compass
+    pub altitude_reference: Option<f64>, // altitude to show relative readings from
+}
+
+impl Sensors {
+    pub fn new(feed: SensorFeed, now: DateTime<Local>) -> Self {
+        let raw = feed.reading(0.0);
+        Self {
+            feed,
+            started: now,
+            raw,
+            history: VecDeque::with_capacity(PRESSURE_SAMPLES),
+            last_sample: None,
+            bearing_memory: None,
+            altitude_reference: None,
+        }
+    }
+
+    pub fn update(&mut self, now: DateTime<Local>, calibration: &Calibration) {
+        let seconds = (now - self.started).num_milliseconds() as f64 / 1000.0;
+        self.raw = self.feed.reading(seconds.max(0.0));
+
+        if self
+            .last_sample
+            .is_none_or(|last| now - last >= TimeDelta::minutes(PRESSURE_SAMPLE_MINUTES))
+        {
+            if self.history.len() == PRESSURE_SAMPLES {
+                self.history.pop_front();
+            }
+            self.history.push_back(self.pressure(calibration));
+            self.last_sample = Some(now);
+        }
+    }
+
+    pub fn heading(&self, calibration: &Calibration) -> f64 {
+        (self.raw.heading + calibration.heading).rem_euclid(360.0)
+    }
+
+    pub fn pressure(&self, calibration: &Calibration) -> f64 {
+        self.raw.pressure + calibration.pressure
+    }
+
+    pub fn altitude(&self, calibration: &Calibration) -> f64 {
+        altitude_at(self.pressure(calibration)) + calibration.altitude
+    }
+
+    pub fn temperature(&self, calibration: &Calibration) -> f64 {
+        self.raw.temperature + calibration.temperature
+    }
+
+    // how each hourly sample differs from the latest, oldest first
+    pub fn pressure_trend(&self) -> Vec<f64> {
+        let latest = self.history.back().copied().unwrap_or_default();
+        self.history.iter().map(|sample| sample - latest).collect()
+    }
+
+    pub fn toggle_bearing_memory(&mut self, calibration: &Calibration) {
+        self.bearing_memory = match self.bearing_memory {
+            Some(_) => None,
+            None => Some(self.heading(calibration)),
+        };
+    }
+
+    pub fn toggle_altitude_reference(&mut self, calibration: &Calibration) {
+        self.altitude_reference = match self.altitude_reference {
+            Some(_) => None,
+            None => Some(self.altitude(calibration)),
+        };
+    }
+}
+
+// sixteen-point compass direction for a heading
+pub fn direction(heading: f64) -> &'static str {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    POINTS[((heading.rem_euclid(360.0) + 11.25) / 22.5) as usize % 16]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(seconds: f64, heading: f64) -> Reading {
+        Reading {
+            seconds,
+            pressure: 1000.0,
+            temperature: 20.0,
+            heading,
+        }
+    }
+
+    #[test]
+    fn csv_skips_header_comments_and_blank_lines() {
+        let data = "seconds,pressure,temperature,heading\n\
+                    # a walk round the block\n\
+                    \n\
+                    0, 1013.2, 18.5, 90\n\
+                    60,1012.8,18.0,95\n";
+        let readings = parse_csv(data).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(
+            readings[1],
+            Reading {
+                seconds: 60.0,
+                pressure: 1012.8,
+                temperature: 18.0,
+                heading: 95.0,
+            }
+        );
+    }
+
+    #[test]
+    fn csv_with_wrong_column_count_is_rejected() {
+        let error = parse_csv("seconds,pressure,temperature,heading\n0,1013.2,18.5\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "line 2 should have 4 values, not 3");
+        assert!(parse_csv("0,1013.2,18.5,90,1\n").is_err());
+        assert!(parse_csv("0,1013.2,warm,90\n").is_err());
+    }
+
+    #[test]
+    fn trace_interpolates_and_holds_at_the_ends() {
+        let feed = SensorFeed::Trace(vec![
+            Reading {
+                seconds: 0.0,
+                pressure: 1000.0,
+                temperature: 10.0,
+                heading: 0.0,
+            },
+            Reading {
+                seconds: 100.0,
+                pressure: 1010.0,
+                temperature: 20.0,
+                heading: 90.0,
+            },
+        ]);
+        let middle = feed.reading(25.0);
+        assert_eq!(middle.pressure, 1002.5);
+        assert_eq!(middle.temperature, 12.5);
+        assert_eq!(middle.heading, 22.5);
+        assert_eq!(feed.reading(-5.0).pressure, 1000.0);
+        assert_eq!(feed.reading(500.0).pressure, 1010.0);
+    }
+
+    #[test]
+    fn trace_heading_turns_the_short_way() {
+        let feed = SensorFeed::Trace(vec![reading(0.0, 350.0), reading(100.0, 10.0)]);
+        assert!((feed.reading(25.0).heading - 355.0).abs() < 1e-9);
+        assert!((feed.reading(75.0).heading - 5.0).abs() < 1e-9);
+        let feed = SensorFeed::Trace(vec![reading(0.0, 10.0), reading(100.0, 350.0)]);
+        assert!((feed.reading(50.0).heading % 360.0).abs() < 1e-9);
+    }
+}
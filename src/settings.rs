@@ -1,7 +1,15 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::databank::{self, Appointment, Record};
+use crate::ir::{self, Device};
+use crate::keymap::Action;
+use crate::sensors::Calibration;
+use crate::tide;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WatchSettings {
@@ -10,6 +18,82 @@ pub struct WatchSettings {
     pub auto_light_duration: u64, // seconds
     pub alarm_enabled: bool,
     pub alarm_time: Option<String>, // HH:MM format
+    #[serde(default)]
+    pub extra_alarms: Vec<DailyAlarm>, // alarms after the first, on models that have several
+    #[serde(default)]
+    pub hourly_signal: bool,
+    #[serde(default)]
+    pub dual_time_offset: i64, // minutes ahead of home time
+    #[serde(default = "default_countdown_minutes")]
+    pub countdown_minutes: u64,
+    #[serde(default)]
+    pub countdown_auto_repeat: bool,
+    #[serde(default)]
+    pub keymap: BTreeMap<String, Action>, // key -> button or app action, on top of the model defaults
+    #[serde(default = "default_theme")]
+    pub theme: String, // preset name or a file in themes/
+    #[serde(default)]
+    pub calibration: Calibration, // pro trek sensor offsets
+    #[serde(default)]
+    pub hand_offset: i64, // seconds the analog hands are ahead of the digital time
+    #[serde(default)]
+    pub telememo: Vec<Record>, // databank records, kept in alphabetical order
+    #[serde(default = "default_tide_port")]
+    pub tide_port: String, // port code for the tide graph
+    #[serde(default)]
+    pub tide_establishments: BTreeMap<String, i64>, // port code -> minutes, overriding the port's own
+    #[serde(default = "ir::default_devices")]
+    pub ir_devices: Vec<Device>, // remote-control device table
+    #[serde(default)]
+    pub memos: Vec<String>, // touch-screen memo pages
+    #[serde(default)]
+    pub schedule: Vec<Appointment>, // kept in time order
+    #[serde(default)]
+    pub game_high_score: u32,
+    #[serde(default)]
+    pub default_model: Option<String>, // last picked in the gallery, where it starts next time
+    #[serde(skip)]
+    pub profile: String, // which profile in the file these settings belong to
+    #[serde(skip)]
+    path: PathBuf, // where these settings were loaded from
+    #[serde(skip)]
+    saved: Map<String, Value>, // the fields as they were last loaded or saved
+}
+
+// a second or third daily alarm
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DailyAlarm {
+    pub enabled: bool,
+    pub time: Option<String>, // HH:MM format
+}
+
+pub const DEFAULT_PATH: &str = "casiotoy.json";
+pub const DEFAULT_PROFILE: &str = "default";
+
+// every profile shares one file, keyed by profile name
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SettingsFile {
+    profiles: BTreeMap<String, WatchSettings>,
+}
+
+// files written before profiles existed hold a single settings object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredSettings {
+    Profiles(SettingsFile),
+    Single(Box<WatchSettings>),
+}
+
+fn default_theme() -> String {
+    "default".to_string()
+}
+
+fn default_tide_port() -> String {
+    tide::PORTS[0].code.to_string()
+}
+
+fn default_countdown_minutes() -> u64 {
+    10
 }
 
 impl Default for WatchSettings {
@@ -20,28 +104,141 @@ impl Default for WatchSettings {
             auto_light_duration: 1, // in seconds
             alarm_enabled: false,
             alarm_time: None,
+            extra_alarms: Vec::new(),
+            hourly_signal: false,
+            dual_time_offset: 0,
+            countdown_minutes: default_countdown_minutes(),
+            countdown_auto_repeat: false,
+            keymap: BTreeMap::new(),
+            theme: default_theme(),
+            calibration: Calibration::default(),
+            hand_offset: 0,
+            telememo: Vec::new(),
+            tide_port: default_tide_port(),
+            tide_establishments: BTreeMap::new(),
+            ir_devices: ir::default_devices(),
+            memos: Vec::new(),
+            schedule: Vec::new(),
+            game_high_score: 0,
+            default_model: None,
+            profile: DEFAULT_PROFILE.to_string(),
+            path: PathBuf::from(DEFAULT_PATH),
+            saved: Map::new(),
         }
     }
 }
 
 impl WatchSettings {
-    pub fn load() -> Result<Self> {
-        let path = "casiotoy.json";
-        if Path::new(path).exists() {
-            let data = fs::read_to_string(path)?;
-            let settings: WatchSettings = serde_json::from_str(&data)?;
-            Ok(settings)
-        } else {
-            // create default settings if none exist
-            let settings = WatchSettings::default();
-            settings.save()?;
-            Ok(settings)
+    pub fn load(path: &Path, profile: &str) -> Result<Self> {
+        let file = read_file(path)?;
+        match file.profiles.get(profile) {
+            Some(settings) => {
+                let mut settings = WatchSettings {
+                    profile: profile.to_string(),
+                    path: path.to_path_buf(),
+                    ..settings.clone()
+                };
+                // a hand-edited telememo may be out of order or too long for the databank
+                settings.telememo.sort();
+                settings.telememo.truncate(databank::RECORDS);
+                settings.saved = fields(&settings)?;
+                Ok(settings)
+            }
+            None => {
+                // create default settings if the profile doesn't exist yet
+                let mut settings = WatchSettings {
+                    profile: profile.to_string(),
+                    path: path.to_path_buf(),
+                    ..WatchSettings::default()
+                };
+                settings.save()?;
+                Ok(settings)
+            }
         }
     }
 
-    pub fn save(&self) -> Result<()> {
-        let data = serde_json::to_string_pretty(self)?;
-        fs::write("casiotoy.json", data)?;
+    // re-reads the file and writes back only the fields changed here, so profiles saved by
+    // someone else in the meantime survive, and so does another watch's change to this one
+    pub fn save(&mut self) -> Result<()> {
+        let mut file = read_file(&self.path)?;
+        let current = fields(self)?;
+        let settings = match file.profiles.get(&self.profile) {
+            Some(stored) => {
+                let mut merged = fields(stored)?;
+                for (name, value) in &current {
+                    if self.saved.get(name) != Some(value) {
+                        merged.insert(name.clone(), value.clone());
+                    }
+                }
+                serde_json::from_value(Value::Object(merged))?
+            }
+            None => self.clone(),
+        };
+        file.profiles.insert(self.profile.clone(), settings);
+
+        let data = serde_json::to_string_pretty(&file)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.saved = current;
         Ok(())
     }
+
+    pub fn profiles(&self) -> Result<Vec<String>> {
+        let file = read_file(&self.path)?;
+        let mut names: Vec<String> = file.profiles.into_keys().collect();
+        if !names.contains(&self.profile) {
+            names.push(self.profile.clone());
+            names.sort();
+        }
+        Ok(names)
+    }
+
+    pub fn switch_profile(&mut self, profile: &str) -> Result<()> {
+        *self = WatchSettings::load(&self.path, profile)?;
+        Ok(())
+    }
+}
+
+fn fields(settings: &WatchSettings) -> Result<Map<String, Value>> {
+    Ok(serde_json::from_value(serde_json::to_value(settings)?)?)
+}
+
+fn read_file(path: &Path) -> Result<SettingsFile> {
+    if !path.exists() {
+        return Ok(SettingsFile::default());
+    }
+
+    let data = fs::read_to_string(path)?;
+    let stored: StoredSettings = serde_json::from_str(&data)
+        .with_context(|| format!("invalid settings file {}", path.display()))?;
+    Ok(match stored {
+        StoredSettings::Profiles(file) => file,
+        StoredSettings::Single(settings) => SettingsFile {
+            profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), *settings)]),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_profile_keeps_both_changes() {
+        let path =
+            std::env::temp_dir().join(format!("casiotoy-shared-{}.json", std::process::id()));
+        let mut first = WatchSettings::load(&path, "shared").unwrap();
+        let mut second = WatchSettings::load(&path, "shared").unwrap();
+
+        first.alarm_time = Some("06:30".to_string());
+        first.save().unwrap();
+        second.theme = "mono".to_string();
+        second.save().unwrap();
+
+        let stored = WatchSettings::load(&path, "shared").unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(stored.alarm_time.as_deref(), Some("06:30"));
+        assert_eq!(stored.theme, "mono");
+    }
 }
@@ -0,0 +1,214 @@
+use anyhow::{Context, Result, bail};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const THEME_DIR: &str = "themes";
+
+// colours for one case variant; custom themes are json files in themes/
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    #[serde(with = "color")]
+    pub case: Color, // watch border
+    #[serde(with = "color")]
+    pub lcd: Color, // lcd background
+    #[serde(with = "color")]
+    pub digits: Color, // main time segments
+    #[serde(with = "color")]
+    pub date: Color, // date and secondary readouts
+    #[serde(with = "color")]
+    pub label: Color, // mode labels like "STOPWATCH"
+    #[serde(with = "color")]
+    pub hands: Color, // analog hands and dial
+    #[serde(with = "color")]
+    pub alarm: Color, // alarm indicator
+    #[serde(with = "color")]
+    pub light: Color, // backlight indicator
+    #[serde(with = "color", default = "default_backlight")]
+    pub backlight: Color, // glow of the led or el panel
+    #[serde(with = "color")]
+    pub running: Color,
+    #[serde(with = "color")]
+    pub stopped: Color,
+}
+
+fn default_backlight() -> Color {
+    Color::Rgb(0x48, 0xc8, 0xb8)
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            case: Color::Blue,
+            lcd: Color::Reset,
+            digits: Color::Green,
+            date: Color::Cyan,
+            label: Color::Yellow,
+            hands: Color::Yellow,
+            alarm: Color::Red,
+            light: Color::Yellow,
+            backlight: default_backlight(),
+            running: Color::Red,
+            stopped: Color::Blue,
+        }
+    }
+}
+
+impl Theme {
+    pub const PRESETS: [&'static str; 6] = [
+        "default",
+        "f91w-1",
+        "a158",
+        "ae1200wh",
+        "mono",
+        "high-contrast",
+    ];
+
+    // NO_COLOR wins over whatever the settings ask for
+    pub fn load(name: &str) -> Result<Self> {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Ok(Self::preset("mono").expect("mono preset exists"));
+        }
+
+        if let Some(theme) = Self::preset(name) {
+            return Ok(theme);
+        }
+
+        let path = Path::new(THEME_DIR).join(format!("{}.json", name));
+        if !path.exists() {
+            bail!(
+                "unknown theme '{}', expected one of {} or a file in {}/",
+                name,
+                Self::PRESETS.join(", "),
+                THEME_DIR
+            );
+        }
+        let data = fs::read_to_string(&path)?;
+        let theme = serde_json::from_str(&data)
+            .with_context(|| format!("invalid theme file {}", path.display()))?;
+        Ok(theme)
+    }
+
+    // presets followed by any theme files found on disk
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = Self::PRESETS.iter().map(|name| name.to_string()).collect();
+        if let Ok(entries) = fs::read_dir(THEME_DIR) {
+            let mut files: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+                .filter(|name| !names.contains(name))
+                .collect();
+            files.sort();
+            names.extend(files);
+        }
+        names
+    }
+
+    fn preset(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            // black resin case, grey-green lcd with black segments
+            "f91w-1" => Self {
+                name: name.to_string(),
+                case: Color::DarkGray,
+                lcd: Color::Rgb(0xb4, 0xbc, 0xa4),
+                digits: Color::Black,
+                date: Color::Rgb(0x30, 0x30, 0x30),
+                label: Color::Rgb(0x20, 0x40, 0x90),
+                hands: Color::Black,
+                alarm: Color::Rgb(0xb0, 0x10, 0x10),
+                light: Color::Rgb(0x20, 0x70, 0x40),
+                backlight: Color::Rgb(0x9c, 0xd4, 0x90),
+                running: Color::Rgb(0xb0, 0x10, 0x10),
+                stopped: Color::Rgb(0x20, 0x40, 0x90),
+            },
+            // stainless steel case around a pale lcd
+            "a158" => Self {
+                name: name.to_string(),
+                case: Color::Gray,
+                lcd: Color::Rgb(0xc8, 0xcc, 0xb8),
+                digits: Color::Black,
+                date: Color::Rgb(0x40, 0x40, 0x40),
+                label: Color::Rgb(0x50, 0x50, 0x50),
+                hands: Color::Black,
+                alarm: Color::Rgb(0x90, 0x10, 0x10),
+                light: Color::Rgb(0x10, 0x60, 0x60),
+                backlight: default_backlight(),
+                running: Color::Rgb(0x90, 0x10, 0x10),
+                stopped: Color::Rgb(0x50, 0x50, 0x50),
+            },
+            // black case with a grey bezel print
+            "ae1200wh" => Self {
+                name: name.to_string(),
+                case: Color::Gray,
+                lcd: Color::Rgb(0xa8, 0xb0, 0xa0),
+                digits: Color::Black,
+                date: Color::Rgb(0x28, 0x28, 0x28),
+                label: Color::DarkGray,
+                hands: Color::Rgb(0x28, 0x28, 0x28),
+                alarm: Color::Rgb(0xa0, 0x20, 0x10),
+                light: Color::Rgb(0x20, 0x60, 0x20),
+                backlight: Color::Rgb(0x9c, 0xd4, 0x90),
+                running: Color::Rgb(0xa0, 0x20, 0x10),
+                stopped: Color::DarkGray,
+            },
+            // terminal default colours only, for NO_COLOR
+            "mono" => Self {
+                name: name.to_string(),
+                case: Color::Reset,
+                lcd: Color::Reset,
+                digits: Color::Reset,
+                date: Color::Reset,
+                label: Color::Reset,
+                hands: Color::Reset,
+                alarm: Color::Reset,
+                light: Color::Reset,
+                backlight: Color::Reset,
+                running: Color::Reset,
+                stopped: Color::Reset,
+            },
+            "high-contrast" => Self {
+                name: name.to_string(),
+                case: Color::White,
+                lcd: Color::Black,
+                digits: Color::White,
+                date: Color::LightYellow,
+                label: Color::LightCyan,
+                hands: Color::White,
+                alarm: Color::LightRed,
+                light: Color::LightYellow,
+                backlight: Color::Reset,
+                running: Color::LightRed,
+                stopped: Color::LightCyan,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+}
+
+// colours are stored as names ("blue") or hex ("#b4bca4") in theme files
+mod color {
+    use ratatui::style::Color;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = match color {
+            Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            other => other.to_string(),
+        };
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Color::from_str(&text).map_err(|_| D::Error::custom(format!("invalid colour '{}'", text)))
+    }
+}
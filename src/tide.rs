@@ -0,0 +1,179 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+// mean time from one new moon to the next
+pub const SYNODIC_MONTH_DAYS: f64 = 29.530588853;
+// a new moon to count from: 2000-01-06 18:14 UTC
+const NEW_MOON_EPOCH: i64 = 947_182_440;
+// the moon comes back to the same meridian about 50 minutes later each day
+const LUNAR_DAY_HOURS: f64 = 24.0 * SYNODIC_MONTH_DAYS / (SYNODIC_MONTH_DAYS - 1.0);
+
+// a port the tide graph can be set to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Port {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub longitude: f64,     // degrees east of greenwich
+    pub establishment: i64, // minutes from the moon crossing the meridian to high water
+}
+
+// the establishment is roughly what tide tables give as high water at full and change
+pub const PORTS: &[Port] = &[
+    Port {
+        code: "TYO",
+        name: "Tokyo",
+        longitude: 139.77,
+        establishment: 330,
+    },
+    Port {
+        code: "HKG",
+        name: "Hong Kong",
+        longitude: 114.17,
+        establishment: 580,
+    },
+    Port {
+        code: "SYD",
+        name: "Sydney",
+        longitude: 151.21,
+        establishment: 525,
+    },
+    Port {
+        code: "HNL",
+        name: "Honolulu",
+        longitude: -157.87,
+        establishment: 210,
+    },
+    Port {
+        code: "SFO",
+        name: "San Francisco",
+        longitude: -122.47,
+        establishment: 700,
+    },
+    Port {
+        code: "NYC",
+        name: "New York",
+        longitude: -74.01,
+        establishment: 493,
+    },
+    Port {
+        code: "LON",
+        name: "London",
+        longitude: -0.09,
+        establishment: 118,
+    },
+    Port {
+        code: "BRS",
+        name: "Brest",
+        longitude: -4.49,
+        establishment: 227,
+    },
+];
+
+impl Port {
+    // unknown codes fall back to the first port
+    pub fn find(code: &str) -> Port {
+        PORTS
+            .iter()
+            .find(|port| port.code.eq_ignore_ascii_case(code))
+            .copied()
+            .unwrap_or(PORTS[0])
+    }
+
+    pub fn next(self) -> Port {
+        let index = PORTS.iter().position(|port| port.code == self.code);
+        PORTS[index.map_or(0, |index| (index + 1) % PORTS.len())]
+    }
+
+    // -1 at low water to 1 at high water, springs reaching further than neaps
+    pub fn tide_level(&self, time: DateTime<Utc>) -> f64 {
+        let elongation = moon_age(time) / SYNODIC_MONTH_DAYS * 360.0;
+        let range = 0.75 + 0.25 * (2.0 * elongation).to_radians().cos();
+        range * self.tide_angle(time).to_radians().cos()
+    }
+
+    pub fn next_high_water(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        self.next_tide(time, 0.0)
+    }
+
+    pub fn next_low_water(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        self.next_tide(time, 180.0)
+    }
+
+    // 0° at high water and 180° at low water, going round twice a lunar day
+    fn tide_angle(&self, time: DateTime<Utc>) -> f64 {
+        let delay = self.establishment as f64 / 60.0 * 360.0 / LUNAR_DAY_HOURS;
+        (2.0 * (moon_hour_angle(time, self.longitude) - delay)).rem_euclid(360.0)
+    }
+
+    fn next_tide(&self, time: DateTime<Utc>, angle: f64) -> DateTime<Utc> {
+        let degrees_per_hour = 2.0 * 360.0 / LUNAR_DAY_HOURS;
+        let to_go = (angle - self.tide_angle(time)).rem_euclid(360.0);
+        time + TimeDelta::seconds((to_go / degrees_per_hour * 3600.0) as i64)
+    }
+}
+
+// days since the last new moon, 0 to a bit over 29.5
+pub fn moon_age(time: DateTime<Utc>) -> f64 {
+    let days = (time.timestamp() - NEW_MOON_EPOCH) as f64 / 86400.0;
+    days.rem_euclid(SYNODIC_MONTH_DAYS)
+}
+
+pub fn phase_name(age: f64) -> &'static str {
+    const PHASES: [&str; 8] = [
+        "NEW", "WAX CRES", "1ST QTR", "WAX GIBB", "FULL", "WAN GIBB", "LAST QTR", "WAN CRES",
+    ];
+    let eighth = SYNODIC_MONTH_DAYS / 8.0;
+    PHASES[((age + eighth / 2.0) / eighth) as usize % 8]
+}
+
+// degrees west the moon is of the meridian at `longitude`; it trails the sun by its elongation
+fn moon_hour_angle(time: DateTime<Utc>, longitude: f64) -> f64 {
+    let hours = (time.timestamp().rem_euclid(86400)) as f64 / 3600.0;
+    let sun = (hours - 12.0) * 15.0 + longitude;
+    let elongation = moon_age(time) / SYNODIC_MONTH_DAYS * 360.0;
+    (sun - elongation).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().to_utc()
+    }
+
+    fn hours_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+        (to - from).num_seconds() as f64 / 3600.0
+    }
+
+    #[test]
+    fn known_new_and_full_moons() {
+        // the mean moon runs up to half a day off the real one
+        let age = moon_age(at("2024-04-08T18:21:00Z"));
+        assert!(!(0.6..=SYNODIC_MONTH_DAYS - 0.6).contains(&age), "{}", age);
+        assert_eq!(phase_name(age), "NEW");
+        let age = moon_age(at("2024-01-25T17:54:00Z"));
+        assert!((age - SYNODIC_MONTH_DAYS / 2.0).abs() < 0.6, "{}", age);
+        assert_eq!(phase_name(age), "FULL");
+    }
+
+    #[test]
+    fn phase_names_go_round_the_month() {
+        let eighth = SYNODIC_MONTH_DAYS / 8.0;
+        assert_eq!(phase_name(0.0), "NEW");
+        assert_eq!(phase_name(2.0 * eighth), "1ST QTR");
+        assert_eq!(phase_name(6.0 * eighth), "LAST QTR");
+        assert_eq!(phase_name(SYNODIC_MONTH_DAYS - 0.1), "NEW");
+    }
+
+    #[test]
+    fn high_water_follows_low_water() {
+        let port = Port::find("BRS");
+        let low = port.next_low_water(at("2026-10-18T00:00:00Z"));
+        let high = port.next_high_water(low);
+        // two tides a lunar day: a quarter of one from low to high, half of one from high to high
+        assert!((hours_between(low, high) - LUNAR_DAY_HOURS / 4.0).abs() < 0.05);
+        let next = port.next_high_water(high + TimeDelta::minutes(1));
+        assert!((hours_between(high, next) - LUNAR_DAY_HOURS / 2.0).abs() < 0.05);
+        assert!(port.tide_level(high) > 0.0 && port.tide_level(low) < 0.0);
+    }
+}
@@ -1,19 +1,42 @@
-use chrono::{DateTime, Local, NaiveTime, Timelike};
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, Timelike};
+use std::time::Instant;
 
 #[derive(Debug, Clone)]
 pub struct TimeManager {
     pub current_time: DateTime<Local>,
+    start_time: DateTime<Local>, // watch time when the clock was started
+    started_at: Instant,
+    speed: f64,        // how fast watch time runs compared to real time
+    offset: TimeDelta, // corrections made since starting, e.g. by radio reception
 }
 
 impl TimeManager {
-    pub fn new() -> Self {
+    pub fn starting_at(start_time: DateTime<Local>, speed: f64) -> Self {
         Self {
-            current_time: Local::now(),
+            current_time: start_time,
+            start_time,
+            started_at: Instant::now(),
+            speed,
+            offset: TimeDelta::zero(),
         }
     }
 
     pub fn update(&mut self) {
-        self.current_time = Local::now();
+        let elapsed = self.started_at.elapsed().as_secs_f64() * self.speed;
+        let elapsed = TimeDelta::milliseconds((elapsed * 1000.0) as i64);
+        // the clock stops at the end of the calendar rather than running off it
+        if let Some(time) = elapsed
+            .checked_add(&self.offset)
+            .and_then(|delta| self.start_time.checked_add_signed(delta))
+        {
+            self.current_time = time;
+        }
+    }
+
+    // sets the watch to `time` from now on, keeping its rate
+    pub fn correct_to(&mut self, time: DateTime<Local>) {
+        self.offset += time - self.current_time;
+        self.current_time = time;
     }
 
     pub fn format_time(&self, format_24h: bool) -> String {
@@ -24,6 +47,16 @@ impl TimeManager {
         }
     }
 
+    // time in a second zone, offset_minutes ahead of home time
+    pub fn format_dual_time(&self, format_24h: bool, offset_minutes: i64) -> String {
+        let time = self.current_time + TimeDelta::minutes(offset_minutes);
+        if format_24h {
+            time.format("%H:%M:%S").to_string()
+        } else {
+            time.format("%I:%M:%S %p").to_string()
+        }
+    }
+
     pub fn format_date(&self, format_us: bool) -> String {
         if format_us {
             self.current_time.format("%m/%d").to_string()
@@ -43,7 +76,7 @@ impl TimeManager {
 
                 let diff = (current.hour() as i32 - time.hour() as i32) * 60
                     + (current.minute() as i32 - time.minute() as i32);
-                diff >= 0 && diff < 1
+                (0..1).contains(&diff)
             } else {
                 false
             }
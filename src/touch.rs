@@ -0,0 +1,145 @@
+use ratatui::layout::{Position, Rect};
+
+use crate::calculator::CalcKey;
+
+// key rows start below the readout, one line each with a gap between rows
+const KEYPAD_TOP: u16 = 4;
+
+// the handwriting grid is this many cells square
+pub const GRID_SIZE: usize = 3;
+
+// letters as drawn on the grid, row by row
+const GLYPHS: [(char, &str); 26] = [
+    ('A', ".#.####.#"),
+    ('B', "##.#####."),
+    ('C', "####..###"),
+    ('D', "##.#.###."),
+    ('E', "#####.###"),
+    ('F', "#####.#.."),
+    ('G', "##.#..###"),
+    ('H', "#.#####.#"),
+    ('I', "###.#.###"),
+    ('J', "..#..###."),
+    ('K', "#.###.#.#"),
+    ('L', "#..#..###"),
+    ('M', "#######.#"),
+    ('N', "##.#.##.#"),
+    ('O', "####.####"),
+    ('P', "#######.."),
+    ('Q', "######..#"),
+    ('R', "##.##.#.#"),
+    ('S', ".##.#.##."),
+    ('T', "###.#..#."),
+    ('U', "#.##.####"),
+    ('V', "#.##.#.#."),
+    ('W', "#.#######"),
+    ('X', "#.#.#.#.#"),
+    ('Y', "#.#.#..#."),
+    ('Z', "##..#..##"),
+];
+
+// what a touch on the face does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchKey {
+    Calc(CalcKey),
+    ClearCalc,
+    Cell(usize), // handwriting grid cell, row by row
+    Write,       // turn the grid into a letter
+    Space,
+    Delete,
+    Erase, // clear the grid
+    NextMemo,
+    Previous,
+    Next,
+    Add,
+    Remove,
+    Shift(i64), // move the appointment by this many minutes
+}
+
+impl TouchKey {
+    pub fn label(self) -> String {
+        match self {
+            TouchKey::Calc(key) => key.symbol().to_string(),
+            TouchKey::ClearCalc => "AC".to_string(),
+            TouchKey::Cell(_) => String::new(),
+            TouchKey::Write => "WRITE".to_string(),
+            TouchKey::Space => "SPACE".to_string(),
+            TouchKey::Delete => "DEL".to_string(),
+            TouchKey::Erase => "ERASE".to_string(),
+            TouchKey::NextMemo => "NEXT".to_string(),
+            TouchKey::Previous => "PREV".to_string(),
+            TouchKey::Next => "NEXT".to_string(),
+            TouchKey::Add => "ADD".to_string(),
+            TouchKey::Remove => "DEL".to_string(),
+            TouchKey::Shift(minutes) => match minutes.abs() {
+                1440 => format!("{:+}D", minutes / 1440),
+                60 => format!("{:+}H", minutes / 60),
+                _ => format!("{:+}M", minutes),
+            },
+        }
+    }
+}
+
+// where each key sits on the lcd; rows share the width evenly
+pub fn key_rects(lcd: Rect, rows: &[Vec<TouchKey>]) -> Vec<(Rect, TouchKey)> {
+    let mut rects = Vec::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        let y = lcd.y + KEYPAD_TOP + row_index as u16 * 2;
+        let width = lcd.width / row.len().max(1) as u16;
+        for (column, key) in row.iter().enumerate() {
+            let rect = Rect {
+                x: lcd.x + column as u16 * width,
+                y,
+                width: width.saturating_sub(1),
+                height: 1,
+            }
+            .intersection(lcd);
+            if !rect.is_empty() {
+                rects.push((rect, *key));
+            }
+        }
+    }
+    rects
+}
+
+pub fn key_at(lcd: Rect, rows: &[Vec<TouchKey>], column: u16, row: u16) -> Option<TouchKey> {
+    key_rects(lcd, rows)
+        .into_iter()
+        .find(|(rect, _)| rect.contains(Position::new(column, row)))
+        .map(|(_, key)| key)
+}
+
+// the 3x3 handwriting pad; cells are touched on and off, then read as a letter
+#[derive(Debug, Clone, Default)]
+pub struct Grid {
+    pub cells: [bool; GRID_SIZE * GRID_SIZE],
+}
+
+impl Grid {
+    pub fn touch(&mut self, cell: usize) {
+        if let Some(on) = self.cells.get_mut(cell) {
+            *on = !*on;
+        }
+    }
+
+    pub fn erase(&mut self) {
+        *self = Self::default();
+    }
+
+    // the letter closest to what's been drawn, None on an empty grid
+    pub fn recognise(&self) -> Option<char> {
+        if !self.cells.contains(&true) {
+            return None;
+        }
+        GLYPHS
+            .iter()
+            .min_by_key(|(_, shape)| {
+                shape
+                    .chars()
+                    .zip(self.cells)
+                    .filter(|(dot, on)| (*dot == '#') != *on)
+                    .count()
+            })
+            .map(|(letter, _)| *letter)
+    }
+}
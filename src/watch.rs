@@ -1,12 +1,305 @@
 use anyhow::Result;
-use std::time::Instant;
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, Timelike};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
 
-use crate::{settings::WatchSettings, time::TimeManager};
+use crate::{
+    analog::Hands,
+    calculator::{CalcKey, Calculator},
+    databank::{self, Appointment, Scheduler, Telememo},
+    definition::{self, Definition, Function},
+    game::Game,
+    ir::{self, Device, IrCode, Remote},
+    keymap::{Button, Keymap},
+    mcu::{self, Mcu},
+    radio::Receiver,
+    script::Script,
+    sensors::Sensors,
+    settings::{DailyAlarm, WatchSettings},
+    theme::Theme,
+    tide::Port,
+    time::TimeManager,
+    touch::{Grid, TouchKey},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WatchModel {
     AE1200,
     F91W,
+    F105W,
+    A158W,
+    A168W,
+    CA53W,
+    DW5600E,
+    GWM5610,
+    DB36,
+    AQ230,
+    PRG240,
+    W800H,
+    MQ24,
+    GLX5600,
+    CMD40,
+    VDB1000,
+    GD8,
+    Firmware,
+    Custom(usize), // loaded from a watch definition file, by index
+}
+
+// the electronics inside the case; models sharing a module share their modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Module {
+    AE1200,   // module 3299
+    F91W,     // module 593, also in the F-105W, A158W and A168W
+    CA53W,    // module 3208, calculator
+    DW5600,   // module 3229 (691 in older cases), square g-shock
+    GWM5610,  // module 3159, square g-shock with multiband radio reception
+    DB36,     // module 1476, databank with a telememo
+    AQ230,    // ana-digi, quartz hands over a small digital module
+    PRG240,   // pro trek with altimeter, barometer, thermometer and compass
+    W800H,    // module 3240, sports watch with three alarms
+    MQ24,     // analog only, three hands and a crown
+    GLX5600,  // g-shock with a tide graph and moon phase
+    CMD40,    // infrared remote control
+    VDB1000,  // touch-screen databank
+    GD8,      // lcd game with a car race
+    Firmware, // 4-bit lcd mcu running a rom image
+    Custom,   // put together from a watch definition file
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Light {
+    Led, // small bulb at the side of the lcd
+    El,  // electroluminescent panel behind the whole lcd
+    None,
+}
+
+impl WatchModel {
+    pub const ALL: [WatchModel; 18] = [
+        WatchModel::AE1200,
+        WatchModel::F91W,
+        WatchModel::F105W,
+        WatchModel::A158W,
+        WatchModel::A168W,
+        WatchModel::CA53W,
+        WatchModel::DW5600E,
+        WatchModel::GWM5610,
+        WatchModel::DB36,
+        WatchModel::AQ230,
+        WatchModel::PRG240,
+        WatchModel::W800H,
+        WatchModel::MQ24,
+        WatchModel::GLX5600,
+        WatchModel::CMD40,
+        WatchModel::VDB1000,
+        WatchModel::GD8,
+        WatchModel::Firmware,
+    ];
+
+    // short name used on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            WatchModel::AE1200 => "ae1200",
+            WatchModel::F91W => "f91w",
+            WatchModel::F105W => "f105w",
+            WatchModel::A158W => "a158w",
+            WatchModel::A168W => "a168w",
+            WatchModel::CA53W => "ca53w",
+            WatchModel::DW5600E => "dw5600e",
+            WatchModel::GWM5610 => "gwm5610",
+            WatchModel::DB36 => "db36",
+            WatchModel::AQ230 => "aq230",
+            WatchModel::PRG240 => "prg240",
+            WatchModel::W800H => "w800h",
+            WatchModel::MQ24 => "mq24",
+            WatchModel::GLX5600 => "glx5600",
+            WatchModel::CMD40 => "cmd40",
+            WatchModel::VDB1000 => "vdb1000",
+            WatchModel::GD8 => "gd8",
+            WatchModel::Firmware => "firmware",
+            WatchModel::Custom(index) => &definition::loaded()[index].name,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            WatchModel::AE1200 => "Casio AE-1200",
+            WatchModel::F91W => "Casio F-91W",
+            WatchModel::F105W => "Casio F-105W",
+            WatchModel::A158W => "Casio A158W",
+            WatchModel::A168W => "Casio A168W",
+            WatchModel::CA53W => "Casio CA-53W",
+            WatchModel::DW5600E => "Casio G-Shock DW-5600E",
+            WatchModel::GWM5610 => "Casio G-Shock GW-M5610",
+            WatchModel::DB36 => "Casio Databank DB-36",
+            WatchModel::AQ230 => "Casio AQ-230",
+            WatchModel::PRG240 => "Casio Pro Trek PRG-240",
+            WatchModel::W800H => "Casio W-800H",
+            WatchModel::MQ24 => "Casio MQ-24",
+            WatchModel::GLX5600 => "Casio G-Shock GLX-5600",
+            WatchModel::CMD40 => "Casio CMD-40",
+            WatchModel::VDB1000 => "Casio VDB-1000",
+            WatchModel::GD8 => "Casio GD-8 Car Race",
+            WatchModel::Firmware => "4-bit MCU Firmware",
+            WatchModel::Custom(index) => &definition::loaded()[index].title,
+        }
+    }
+
+    pub fn module(self) -> Module {
+        match self {
+            WatchModel::AE1200 => Module::AE1200,
+            WatchModel::F91W | WatchModel::F105W | WatchModel::A158W | WatchModel::A168W => {
+                Module::F91W
+            }
+            WatchModel::CA53W => Module::CA53W,
+            WatchModel::DW5600E => Module::DW5600,
+            WatchModel::GWM5610 => Module::GWM5610,
+            WatchModel::DB36 => Module::DB36,
+            WatchModel::AQ230 => Module::AQ230,
+            WatchModel::PRG240 => Module::PRG240,
+            WatchModel::W800H => Module::W800H,
+            WatchModel::MQ24 => Module::MQ24,
+            WatchModel::GLX5600 => Module::GLX5600,
+            WatchModel::CMD40 => Module::CMD40,
+            WatchModel::VDB1000 => Module::VDB1000,
+            WatchModel::GD8 => Module::GD8,
+            WatchModel::Firmware => Module::Firmware,
+            WatchModel::Custom(_) => Module::Custom,
+        }
+    }
+
+    // touch-screen models take mouse clicks on the lcd as well as buttons
+    pub fn touch_screen(self) -> bool {
+        matches!(self, WatchModel::VDB1000)
+    }
+
+    pub fn light(self) -> Light {
+        match self {
+            WatchModel::AE1200
+            | WatchModel::F91W
+            | WatchModel::A158W
+            | WatchModel::CA53W
+            | WatchModel::DB36
+            | WatchModel::AQ230
+            | WatchModel::PRG240
+            | WatchModel::W800H
+            | WatchModel::CMD40
+            | WatchModel::VDB1000
+            | WatchModel::GD8
+            | WatchModel::Firmware => Light::Led,
+            WatchModel::F105W
+            | WatchModel::A168W
+            | WatchModel::DW5600E
+            | WatchModel::GWM5610
+            | WatchModel::GLX5600 => Light::El,
+            WatchModel::MQ24 => Light::None,
+            WatchModel::Custom(index) => definition::loaded()[index].light,
+        }
+    }
+
+    // one line about the watch for the model picker
+    pub fn description(self) -> &'static str {
+        match self {
+            WatchModel::AE1200 => "World timer with a map of the globe, module 3299",
+            WatchModel::F91W => "The classic three-button digital, module 593",
+            WatchModel::F105W => "F-91W module behind an EL backlight",
+            WatchModel::A158W => "F-91W module in a metal case",
+            WatchModel::A168W => "F-91W module in a metal case with an EL backlight",
+            WatchModel::CA53W => "Calculator watch, module 3208",
+            WatchModel::DW5600E => "The square G-Shock, module 3229",
+            WatchModel::GWM5610 => "Square G-Shock set by radio time signals",
+            WatchModel::DB36 => "Databank with a telephone memo",
+            WatchModel::AQ230 => "Ana-digi with quartz hands over a small LCD",
+            WatchModel::PRG240 => "Pro Trek with triple sensor and compass",
+            WatchModel::W800H => "Sports watch with three alarms, module 3240",
+            WatchModel::MQ24 => "Analog only: three hands and a crown",
+            WatchModel::GLX5600 => "G-Lide surf G-Shock with tides and moon",
+            WatchModel::CMD40 => "Infrared remote control for TVs and video",
+            WatchModel::VDB1000 => "Touch-screen databank",
+            WatchModel::GD8 => "LCD game watch with a car race",
+            WatchModel::Firmware => "4-bit microcontroller running a ROM image",
+            WatchModel::Custom(_) => "Fan-made, from a definition file",
+        }
+    }
+
+    // what the modes and the case have to offer, for the model picker
+    pub fn features(self) -> Vec<String> {
+        let features: &[&str] = match self {
+            WatchModel::AE1200 => &["World time", "Daily alarm", "Countdown timer", "Stopwatch"],
+            WatchModel::F91W | WatchModel::F105W | WatchModel::A158W | WatchModel::A168W => {
+                &["Daily alarm and hourly signal", "1/100s stopwatch"]
+            }
+            WatchModel::CA53W => &[
+                "8-digit calculator",
+                "Daily alarm",
+                "Stopwatch",
+                "Dual time",
+            ],
+            WatchModel::DW5600E => &["1/100s split stopwatch", "Auto-repeat timer", "Daily alarm"],
+            WatchModel::GWM5610 => &[
+                "Radio time-signal reception",
+                "1/100s split stopwatch",
+                "Auto-repeat timer",
+                "Daily alarm",
+            ],
+            WatchModel::DB36 => &["30-record telememo", "Daily alarm", "Stopwatch"],
+            WatchModel::AQ230 => &["Crown-set hands", "Dual time", "Daily alarm", "Stopwatch"],
+            WatchModel::PRG240 => &["Compass", "Altimeter", "Barometer", "Thermometer"],
+            WatchModel::W800H => &[
+                "Dual time",
+                "1/100s split stopwatch",
+                "Countdown timer",
+                "Three daily alarms",
+            ],
+            WatchModel::MQ24 => &["Ticking second hand", "Crown-set hands"],
+            WatchModel::GLX5600 => &["Tide graph", "Moon phase", "Stopwatch", "Daily alarm"],
+            WatchModel::CMD40 => &["NEC and RC5 remote codes", "Learn mode", "Daily alarm"],
+            WatchModel::VDB1000 => &["Touch calculator", "Handwritten memos", "Scheduler"],
+            WatchModel::GD8 => &["Car race with a saved high score", "Daily alarm"],
+            WatchModel::Firmware => &["SM510-style core", "Assembler", "Demo clock ROM"],
+            WatchModel::Custom(index) => {
+                return definition::loaded()[index]
+                    .modes
+                    .iter()
+                    .map(|mode| format!("{} ({})", mode.name, mode.function.name()))
+                    .collect();
+            }
+        };
+        let light = match self.light() {
+            Light::Led => Some("LED light"),
+            Light::El => Some("EL backlight"),
+            Light::None => None,
+        };
+        features
+            .iter()
+            .copied()
+            .chain(light)
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn definition(self) -> Option<&'static Definition> {
+        match self {
+            WatchModel::Custom(index) => definition::loaded().get(index),
+            _ => None,
+        }
+    }
+
+    // the built-in models followed by any loaded from definition files
+    pub fn available() -> Vec<WatchModel> {
+        WatchModel::ALL
+            .into_iter()
+            .chain((0..definition::loaded().len()).map(WatchModel::Custom))
+            .collect()
+    }
+
+    // accepts "f91w", "F-91W" and the like
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace(['-', '_', ' '], "");
+        WatchModel::available()
+            .into_iter()
+            .find(|model| model.name() == name)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,46 +319,960 @@ pub enum F91WMode {
     Stopwatch,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CA53WMode {
+    Time,
+    Calculator,
+    Alarm,
+    Stopwatch,
+    DualTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DW5600Mode {
+    Time,
+    Stopwatch,
+    Timer,
+    Alarm,
+}
+
+// the dw5600 modes plus a receive mode showing the last reception
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GWM5610Mode {
+    Time,
+    Receive,
+    Stopwatch,
+    Timer,
+    Alarm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DB36Mode {
+    Time,
+    Telememo,
+    Alarm,
+    Stopwatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AQ230Mode {
+    Time,
+    DualTime,
+    Alarm,
+    Stopwatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PRG240Mode {
+    Time,
+    Compass,
+    Altimeter,
+    Barometer,
+    Thermometer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum W800HMode {
+    Time,
+    DualTime,
+    Stopwatch,
+    Timer,
+    Alarm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GLX5600Mode {
+    Time,
+    Tide,
+    Moon,
+    Stopwatch,
+    Alarm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CMD40Mode {
+    Time,
+    Remote,
+    Learn,
+    Alarm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VDB1000Mode {
+    Time,
+    Calculator,
+    Memo,
+    Schedule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GD8Mode {
+    Time,
+    Game,
+    Alarm,
+}
+
+// a module's modes in the order the mode button goes through them, which the manual reads too
+pub trait ModeCycle: Copy + PartialEq + 'static {
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    // what start/stop, reset and alarm do in the mode
+    fn controls(self) -> &'static [(Button, Control)];
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+// everything a button can do; a press looks its button up in the current mode's table and does
+// what it finds there, and the manual prints the same table with these labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    NextMode,
+    Light,
+    AlarmTest,
+    AlarmSignal,
+    AlarmSlot,
+    NextAlarmSlot,
+    StopwatchStartStop,
+    StopwatchReset,
+    StopwatchSplitReset,
+    TimerStartStop,
+    TimerReset,
+    CountdownStartStop,
+    CountdownReset,
+    CountdownStart,
+    DualTimeForward,
+    DualTimeHome,
+    CalculatorClear,
+    Reception,
+    NextRecord,
+    PreviousRecord,
+    EditRecord,
+    NextCharacter,
+    PreviousCharacter,
+    NextPosition,
+    StoreRecord,
+    BearingMemory,
+    ReferenceAltitude,
+    Calibrate,
+    CalibrationUp,
+    CalibrationDown,
+    SaveCalibration,
+    TideForward,
+    TideNow,
+    NextPort,
+    MoonForward,
+    MoonToday,
+    SendCode,
+    NextKey,
+    NextDevice,
+    NextLearned,
+    StoreCode,
+    EraseGrid,
+    SteerLeft,
+    SteerRight,
+    StartPause,
+    PullCrown,
+    PushCrown,
+    HandsForward,
+    HandsBack,
+    Rom,    // the button goes to the firmware as it is
+    Script, // the button goes to the mode's script
+}
+
+impl Control {
+    pub fn label(self) -> &'static str {
+        match self {
+            Control::NextMode => "next mode",
+            Control::Light => "light",
+            Control::AlarmTest => "alarm on/off, set 1 min ahead",
+            Control::AlarmSignal => "alarm/signal on/off",
+            Control::AlarmSlot => "this alarm on/off",
+            Control::NextAlarmSlot => "next alarm or the signal",
+            Control::StopwatchStartStop | Control::TimerStartStop | Control::CountdownStartStop => {
+                "start/stop"
+            }
+            Control::StopwatchReset | Control::TimerReset => "reset",
+            Control::StopwatchSplitReset => "split/reset",
+            Control::CountdownReset => "reset, again for auto-repeat",
+            Control::CountdownStart => "start time +1 min",
+            Control::DualTimeForward | Control::TideForward => "+1 hour",
+            Control::DualTimeHome => "back to home time",
+            Control::CalculatorClear => "clear",
+            Control::Reception => "start/cancel reception",
+            Control::NextRecord => "next record",
+            Control::PreviousRecord => "previous record",
+            Control::EditRecord => "edit the record",
+            Control::NextCharacter => "next character",
+            Control::PreviousCharacter => "previous character",
+            Control::NextPosition => "next position",
+            Control::StoreRecord => "store the record",
+            Control::BearingMemory => "bearing memory on/off",
+            Control::ReferenceAltitude => "reference altitude on/off",
+            Control::Calibrate => "calibrate",
+            Control::CalibrationUp => "+1 step",
+            Control::CalibrationDown => "-1 step",
+            Control::SaveCalibration => "save the calibration",
+            Control::TideNow => "back to now",
+            Control::NextPort => "next port",
+            Control::MoonForward => "+1 day",
+            Control::MoonToday => "back to today",
+            Control::SendCode => "send the code",
+            Control::NextKey => "next key",
+            Control::NextDevice => "next device",
+            Control::NextLearned => "next learned code",
+            Control::StoreCode => "store the code",
+            Control::EraseGrid => "erase the grid",
+            Control::SteerLeft => "steer left",
+            Control::SteerRight => "steer right",
+            Control::StartPause => "start/pause",
+            Control::PullCrown => "pull the crown to set the hands",
+            Control::PushCrown => "push the crown in",
+            Control::HandsForward => "hands +1 min",
+            Control::HandsBack => "hands -1 min",
+            Control::Rom => "up to the rom",
+            Control::Script => "up to the script",
+        }
+    }
+
+    // the few characters the lcd has room for; a button passed straight on is named after itself
+    pub fn hint(self, button: Button) -> &'static str {
+        match self {
+            Control::NextMode => "for mode",
+            Control::Light => "for light",
+            Control::AlarmTest => "alm +1 min",
+            Control::AlarmSignal => "alm/sig",
+            Control::AlarmSlot => "on/off",
+            Control::NextAlarmSlot => "next",
+            Control::StopwatchStartStop | Control::TimerStartStop | Control::CountdownStartStop => {
+                "start/stop"
+            }
+            Control::StopwatchReset | Control::TimerReset => "reset",
+            Control::StopwatchSplitReset => "split/reset",
+            Control::CountdownReset => "reset/repeat",
+            Control::CountdownStart | Control::HandsForward => "+1 min",
+            Control::DualTimeForward | Control::TideForward => "+1 hour",
+            Control::DualTimeHome => "home",
+            Control::CalculatorClear => "clear",
+            Control::Reception => "receive/cancel",
+            Control::NextRecord | Control::NextPosition | Control::NextLearned => "next",
+            Control::PreviousRecord => "prev",
+            Control::EditRecord => "input",
+            Control::NextCharacter | Control::CalibrationUp => "+",
+            Control::PreviousCharacter | Control::CalibrationDown => "-",
+            Control::StoreRecord | Control::StoreCode => "store",
+            Control::BearingMemory => "bearing memory",
+            Control::ReferenceAltitude => "relative",
+            Control::Calibrate => "calibrate",
+            Control::SaveCalibration => "save",
+            Control::TideNow => "now",
+            Control::NextPort => "port",
+            Control::MoonForward => "+1 day",
+            Control::MoonToday => "today",
+            Control::SendCode => "send",
+            Control::NextKey => "key",
+            Control::NextDevice => "device",
+            Control::EraseGrid => "erase",
+            Control::SteerLeft => "left",
+            Control::SteerRight => "right",
+            Control::StartPause => "start/pause",
+            Control::PullCrown => "set hands",
+            Control::PushCrown => "push crown",
+            Control::HandsBack => "-1 min",
+            Control::Rom | Control::Script => match button {
+                Button::Mode => "M",
+                Button::StartStop => "S",
+                Button::Reset => "R",
+                Button::Light => "L",
+                Button::Alarm => "A",
+                Button::CrownPull => "crown",
+                Button::CrownForward => "crown +",
+                Button::CrownBack => "crown -",
+                Button::Calc(_) => "",
+            },
+        }
+    }
+}
+
+// what the alarm button does wherever a mode has no other use for it
+pub const ALARM_CONTROL: (Button, Control) = (Button::Alarm, Control::AlarmTest);
+pub const ALARM_SIGNAL_CONTROLS: &[(Button, Control)] =
+    &[(Button::StartStop, Control::AlarmSignal), ALARM_CONTROL];
+const STOPWATCH_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::StopwatchStartStop),
+    (Button::Reset, Control::StopwatchReset),
+    ALARM_CONTROL,
+];
+pub const SPLIT_STOPWATCH_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::StopwatchStartStop),
+    (Button::Reset, Control::StopwatchSplitReset),
+    ALARM_CONTROL,
+];
+// the ae-1200's timer counts up, separately from its stopwatch
+const TIMER_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::TimerStartStop),
+    (Button::Reset, Control::TimerReset),
+    ALARM_CONTROL,
+];
+pub const COUNTDOWN_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::CountdownStartStop),
+    (Button::Reset, Control::CountdownReset),
+    (Button::Alarm, Control::CountdownStart),
+];
+pub const DUAL_TIME_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::DualTimeForward),
+    (Button::Reset, Control::DualTimeHome),
+    ALARM_CONTROL,
+];
+// every button goes to the rom, which decides what it means
+const FIRMWARE_CONTROLS: &[(Button, Control)] = &[
+    (Button::Mode, Control::Rom),
+    (Button::StartStop, Control::Rom),
+    (Button::Reset, Control::Rom),
+    (Button::Alarm, Control::Rom),
+    (Button::CrownPull, Control::Rom),
+    (Button::CrownForward, Control::Rom),
+    (Button::CrownBack, Control::Rom),
+];
+// the hourly signal has no time to set, so there's nothing for the alarm test to do
+const W800H_SIGNAL_CONTROLS: &[(Button, Control)] = &[
+    (Button::StartStop, Control::AlarmSlot),
+    (Button::Reset, Control::NextAlarmSlot),
+];
+const TELEMEMO_INPUT_CONTROLS: &[(Button, Control)] = &[
+    (Button::Mode, Control::StoreRecord),
+    (Button::StartStop, Control::NextCharacter),
+    (Button::Reset, Control::PreviousCharacter),
+    (Button::Alarm, Control::NextPosition),
+];
+const CALIBRATION_CONTROLS: &[(Button, Control)] = &[
+    (Button::Mode, Control::SaveCalibration),
+    (Button::StartStop, Control::CalibrationUp),
+    (Button::Reset, Control::CalibrationDown),
+    (Button::Alarm, Control::SaveCalibration),
+];
+const CROWN_PULLED_CONTROLS: &[(Button, Control)] = &[
+    (Button::CrownPull, Control::PushCrown),
+    (Button::CrownForward, Control::HandsForward),
+    (Button::CrownBack, Control::HandsBack),
+];
+
+impl ModeCycle for WatchMode {
+    const ALL: &'static [Self] = &[
+        WatchMode::Home,
+        WatchMode::WorldTime,
+        WatchMode::Alarm,
+        WatchMode::Timer,
+        WatchMode::Stopwatch,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            WatchMode::Home => "Home time",
+            WatchMode::WorldTime => "World time",
+            WatchMode::Alarm => "Alarm",
+            WatchMode::Timer => "Timer",
+            WatchMode::Stopwatch => "Stopwatch",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            WatchMode::Home | WatchMode::WorldTime | WatchMode::Alarm => &[ALARM_CONTROL],
+            WatchMode::Timer => TIMER_CONTROLS,
+            WatchMode::Stopwatch => STOPWATCH_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for F91WMode {
+    const ALL: &'static [Self] = &[F91WMode::Time, F91WMode::Alarm, F91WMode::Stopwatch];
+
+    fn name(self) -> &'static str {
+        match self {
+            F91WMode::Time => "Time",
+            F91WMode::Alarm => "Alarm",
+            F91WMode::Stopwatch => "Stopwatch",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            F91WMode::Time => &[ALARM_CONTROL],
+            F91WMode::Alarm => ALARM_SIGNAL_CONTROLS,
+            F91WMode::Stopwatch => STOPWATCH_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for CA53WMode {
+    const ALL: &'static [Self] = &[
+        CA53WMode::Time,
+        CA53WMode::Calculator,
+        CA53WMode::Alarm,
+        CA53WMode::Stopwatch,
+        CA53WMode::DualTime,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CA53WMode::Time => "Time",
+            CA53WMode::Calculator => "Calculator",
+            CA53WMode::Alarm => "Alarm",
+            CA53WMode::Stopwatch => "Stopwatch",
+            CA53WMode::DualTime => "Dual time",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            CA53WMode::Time => &[ALARM_CONTROL],
+            CA53WMode::Calculator => &[(Button::Reset, Control::CalculatorClear), ALARM_CONTROL],
+            CA53WMode::Alarm => ALARM_SIGNAL_CONTROLS,
+            CA53WMode::Stopwatch => STOPWATCH_CONTROLS,
+            CA53WMode::DualTime => DUAL_TIME_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for DW5600Mode {
+    const ALL: &'static [Self] = &[
+        DW5600Mode::Time,
+        DW5600Mode::Stopwatch,
+        DW5600Mode::Timer,
+        DW5600Mode::Alarm,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DW5600Mode::Time => "Time",
+            DW5600Mode::Stopwatch => "Stopwatch",
+            DW5600Mode::Timer => "Timer",
+            DW5600Mode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            DW5600Mode::Time => &[ALARM_CONTROL],
+            DW5600Mode::Stopwatch => SPLIT_STOPWATCH_CONTROLS,
+            DW5600Mode::Timer => COUNTDOWN_CONTROLS,
+            DW5600Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for GWM5610Mode {
+    const ALL: &'static [Self] = &[
+        GWM5610Mode::Time,
+        GWM5610Mode::Receive,
+        GWM5610Mode::Stopwatch,
+        GWM5610Mode::Timer,
+        GWM5610Mode::Alarm,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            GWM5610Mode::Time => "Time",
+            GWM5610Mode::Receive => "Receive",
+            GWM5610Mode::Stopwatch => "Stopwatch",
+            GWM5610Mode::Timer => "Timer",
+            GWM5610Mode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            GWM5610Mode::Time => &[ALARM_CONTROL],
+            GWM5610Mode::Receive => &[(Button::Reset, Control::Reception), ALARM_CONTROL],
+            GWM5610Mode::Stopwatch => SPLIT_STOPWATCH_CONTROLS,
+            GWM5610Mode::Timer => COUNTDOWN_CONTROLS,
+            GWM5610Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for DB36Mode {
+    const ALL: &'static [Self] = &[
+        DB36Mode::Time,
+        DB36Mode::Telememo,
+        DB36Mode::Alarm,
+        DB36Mode::Stopwatch,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DB36Mode::Time => "Time",
+            DB36Mode::Telememo => "Telememo",
+            DB36Mode::Alarm => "Alarm",
+            DB36Mode::Stopwatch => "Stopwatch",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            DB36Mode::Time => &[ALARM_CONTROL],
+            DB36Mode::Telememo => &[
+                (Button::StartStop, Control::NextRecord),
+                (Button::Reset, Control::PreviousRecord),
+                (Button::Alarm, Control::EditRecord),
+            ],
+            DB36Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+            DB36Mode::Stopwatch => STOPWATCH_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for AQ230Mode {
+    const ALL: &'static [Self] = &[
+        AQ230Mode::Time,
+        AQ230Mode::DualTime,
+        AQ230Mode::Alarm,
+        AQ230Mode::Stopwatch,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            AQ230Mode::Time => "Time",
+            AQ230Mode::DualTime => "Dual time",
+            AQ230Mode::Alarm => "Alarm",
+            AQ230Mode::Stopwatch => "Stopwatch",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            AQ230Mode::Time => &[ALARM_CONTROL],
+            AQ230Mode::DualTime => DUAL_TIME_CONTROLS,
+            AQ230Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+            AQ230Mode::Stopwatch => STOPWATCH_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for PRG240Mode {
+    const ALL: &'static [Self] = &[
+        PRG240Mode::Time,
+        PRG240Mode::Compass,
+        PRG240Mode::Altimeter,
+        PRG240Mode::Barometer,
+        PRG240Mode::Thermometer,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            PRG240Mode::Time => "Time",
+            PRG240Mode::Compass => "Compass",
+            PRG240Mode::Altimeter => "Altimeter",
+            PRG240Mode::Barometer => "Barometer",
+            PRG240Mode::Thermometer => "Thermometer",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            PRG240Mode::Time => &[ALARM_CONTROL],
+            PRG240Mode::Compass => &[
+                (Button::StartStop, Control::BearingMemory),
+                (Button::Alarm, Control::Calibrate),
+            ],
+            PRG240Mode::Altimeter => &[
+                (Button::StartStop, Control::ReferenceAltitude),
+                (Button::Alarm, Control::Calibrate),
+            ],
+            PRG240Mode::Barometer | PRG240Mode::Thermometer => {
+                &[(Button::Alarm, Control::Calibrate)]
+            }
+        }
+    }
+}
+
+impl ModeCycle for W800HMode {
+    const ALL: &'static [Self] = &[
+        W800HMode::Time,
+        W800HMode::DualTime,
+        W800HMode::Stopwatch,
+        W800HMode::Timer,
+        W800HMode::Alarm,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            W800HMode::Time => "Time",
+            W800HMode::DualTime => "Dual time",
+            W800HMode::Stopwatch => "Stopwatch",
+            W800HMode::Timer => "Timer",
+            W800HMode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            W800HMode::Time => &[ALARM_CONTROL],
+            W800HMode::DualTime => DUAL_TIME_CONTROLS,
+            W800HMode::Stopwatch => SPLIT_STOPWATCH_CONTROLS,
+            W800HMode::Timer => COUNTDOWN_CONTROLS,
+            W800HMode::Alarm => &[
+                (Button::StartStop, Control::AlarmSlot),
+                (Button::Reset, Control::NextAlarmSlot),
+                ALARM_CONTROL,
+            ],
+        }
+    }
+}
+
+impl ModeCycle for GLX5600Mode {
+    const ALL: &'static [Self] = &[
+        GLX5600Mode::Time,
+        GLX5600Mode::Tide,
+        GLX5600Mode::Moon,
+        GLX5600Mode::Stopwatch,
+        GLX5600Mode::Alarm,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            GLX5600Mode::Time => "Time",
+            GLX5600Mode::Tide => "Tide",
+            GLX5600Mode::Moon => "Moon",
+            GLX5600Mode::Stopwatch => "Stopwatch",
+            GLX5600Mode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            GLX5600Mode::Time => &[ALARM_CONTROL],
+            GLX5600Mode::Tide => &[
+                (Button::StartStop, Control::TideForward),
+                (Button::Reset, Control::TideNow),
+                (Button::Alarm, Control::NextPort),
+            ],
+            GLX5600Mode::Moon => &[
+                (Button::StartStop, Control::MoonForward),
+                (Button::Reset, Control::MoonToday),
+            ],
+            GLX5600Mode::Stopwatch => SPLIT_STOPWATCH_CONTROLS,
+            GLX5600Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for CMD40Mode {
+    const ALL: &'static [Self] = &[
+        CMD40Mode::Time,
+        CMD40Mode::Remote,
+        CMD40Mode::Learn,
+        CMD40Mode::Alarm,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CMD40Mode::Time => "Time",
+            CMD40Mode::Remote => "Remote",
+            CMD40Mode::Learn => "Learn",
+            CMD40Mode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            CMD40Mode::Time => &[ALARM_CONTROL],
+            CMD40Mode::Remote => &[
+                (Button::StartStop, Control::SendCode),
+                (Button::Reset, Control::NextKey),
+                (Button::Alarm, Control::NextDevice),
+            ],
+            CMD40Mode::Learn => &[
+                (Button::StartStop, Control::NextLearned),
+                (Button::Alarm, Control::StoreCode),
+            ],
+            CMD40Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+        }
+    }
+}
+
+impl ModeCycle for VDB1000Mode {
+    const ALL: &'static [Self] = &[
+        VDB1000Mode::Time,
+        VDB1000Mode::Calculator,
+        VDB1000Mode::Memo,
+        VDB1000Mode::Schedule,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            VDB1000Mode::Time => "Time",
+            VDB1000Mode::Calculator => "Calculator",
+            VDB1000Mode::Memo => "Memo",
+            VDB1000Mode::Schedule => "Schedule",
+        }
+    }
+
+    // the rest is on the touch screen
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            VDB1000Mode::Time | VDB1000Mode::Schedule => &[],
+            VDB1000Mode::Calculator => &[(Button::Reset, Control::CalculatorClear)],
+            VDB1000Mode::Memo => &[(Button::Reset, Control::EraseGrid)],
+        }
+    }
+}
+
+impl ModeCycle for GD8Mode {
+    const ALL: &'static [Self] = &[GD8Mode::Time, GD8Mode::Game, GD8Mode::Alarm];
+
+    fn name(self) -> &'static str {
+        match self {
+            GD8Mode::Time => "Time",
+            GD8Mode::Game => "Game",
+            GD8Mode::Alarm => "Alarm",
+        }
+    }
+
+    fn controls(self) -> &'static [(Button, Control)] {
+        match self {
+            GD8Mode::Time => &[ALARM_CONTROL],
+            GD8Mode::Game => &[
+                (Button::Reset, Control::SteerLeft),
+                (Button::StartStop, Control::SteerRight),
+                (Button::Alarm, Control::StartPause),
+            ],
+            GD8Mode::Alarm => ALARM_SIGNAL_CONTROLS,
+        }
+    }
+}
+
+// the hardware only one model has, built for that model and nothing else
+pub enum Peripherals {
+    None,
+    Radio(Receiver),  // gw-m5610
+    Sensors(Sensors), // prg-240
+    Remote(Remote),   // cmd-40
+    Mcu(Mcu),         // firmware
+}
+
+impl Peripherals {
+    pub fn receiver(&self) -> Option<&Receiver> {
+        match self {
+            Peripherals::Radio(receiver) => Some(receiver),
+            _ => None,
+        }
+    }
+
+    fn receiver_mut(&mut self) -> Option<&mut Receiver> {
+        match self {
+            Peripherals::Radio(receiver) => Some(receiver),
+            _ => None,
+        }
+    }
+
+    pub fn sensors(&self) -> Option<&Sensors> {
+        match self {
+            Peripherals::Sensors(sensors) => Some(sensors),
+            _ => None,
+        }
+    }
+
+    fn sensors_mut(&mut self) -> Option<&mut Sensors> {
+        match self {
+            Peripherals::Sensors(sensors) => Some(sensors),
+            _ => None,
+        }
+    }
+
+    pub fn remote(&self) -> Option<&Remote> {
+        match self {
+            Peripherals::Remote(remote) => Some(remote),
+            _ => None,
+        }
+    }
+
+    pub fn remote_mut(&mut self) -> Option<&mut Remote> {
+        match self {
+            Peripherals::Remote(remote) => Some(remote),
+            _ => None,
+        }
+    }
+
+    pub fn mcu(&self) -> Option<&Mcu> {
+        match self {
+            Peripherals::Mcu(mcu) => Some(mcu),
+            _ => None,
+        }
+    }
+
+    fn mcu_mut(&mut self) -> Option<&mut Mcu> {
+        match self {
+            Peripherals::Mcu(mcu) => Some(mcu),
+            _ => None,
+        }
+    }
+}
+
 pub struct Watch {
     pub model: WatchModel,
     pub mode: WatchMode,
     pub f91w_mode: F91WMode,
+    pub ca53w_mode: CA53WMode,
+    pub dw5600_mode: DW5600Mode,
+    pub gwm5610_mode: GWM5610Mode,
+    pub db36_mode: DB36Mode,
+    pub aq230_mode: AQ230Mode,
+    pub prg240_mode: PRG240Mode,
+    pub w800h_mode: W800HMode,
+    pub glx5600_mode: GLX5600Mode,
+    pub almanac_offset: TimeDelta, // how far ahead of now the tide and moon modes look
+    pub cmd40_mode: CMD40Mode,
+    pub ir_device: usize, // index into the settings' device table
+    pub ir_code: usize,   // index into that device's codes
+    pub vdb1000_mode: VDB1000Mode,
+    pub grid: Grid,
+    pub memo_index: usize, // one past the memos for a new page
+    pub scheduler: Scheduler,
+    pub gd8_mode: GD8Mode,
+    pub game: Game,
+    pub custom_mode: usize,           // index into the definition's modes
+    pub scripts: Vec<Option<Script>>, // one for each of the definition's script modes
+    mcu_started: DateTime<Local>,     // watch time at the mcu's reset
+    pub alarm_slot: usize, // alarm shown in alarm mode; one past the alarms is the hourly signal
+    pub calculator: Calculator,
+    pub telememo: Telememo,
+    pub hands: Hands,
+    pub calibrating: bool, // adjusting the sensor shown in the current mode
     pub settings: WatchSettings,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    theme_choice: String, // the settings value the theme was loaded from
     pub time_manager: TimeManager,
+    pub peripherals: Peripherals,
     pub stopwatch_time: u64, // milliseconds
     pub stopwatch_running: bool,
     pub stopwatch_start_time: Option<Instant>,
-    pub timer_time: u64, // milliseconds
+    pub stopwatch_split: Option<u64>, // frozen split reading, milliseconds
+    pub timer_time: u64,              // milliseconds
     pub timer_running: bool,
     pub timer_start_time: Option<Instant>,
+    pub countdown_remaining: u64, // milliseconds
+    pub countdown_running: bool,
+    pub countdown_start_time: Option<Instant>,
+    countdown_started_from: u64, // remaining milliseconds when last started
+    countdown_set_to: u64,       // start time in minutes, to notice the menu changing it
+    pub countdown_beep_start_time: Option<Instant>, // set while time-up sounds
     pub light_on: bool,
     pub light_start_time: Option<Instant>,
+    pub alarm_start_time: Option<Instant>, // set while the alarm is sounding
+    pub signal_start_time: Option<Instant>, // set while the hourly signal beeps
+    last_alarm: Option<NaiveDateTime>,
+    last_signal: Option<NaiveDateTime>,
+    last_auto_reception: Option<NaiveDateTime>,
 }
 
+// the w-800h has alarms 1 to 3
+const W800H_ALARMS: usize = 3;
+// how long the alarm sounds if nobody presses a button
+const ALARM_DURATION_SECS: u64 = 20;
+const SIGNAL_DURATION_SECS: u64 = 1;
+const COUNTDOWN_BEEP_SECS: u64 = 10;
+// chronographs roll over after 23:59'59"99
+const STOPWATCH_RANGE_MS: u64 = 24 * 60 * 60 * 1000;
+// ten seconds of instructions
+const MCU_MAX_CYCLES_PER_UPDATE: u64 = 10 * mcu::CLOCK_HZ;
+// radio watches try on the hour from midnight until this hour
+const LAST_AUTO_RECEPTION_HOUR: u32 = 5;
+
 impl Watch {
-    pub fn new(model: WatchModel) -> Result<Self> {
-        let settings = WatchSettings::load()?;
-        let time_manager = TimeManager::new();
+    pub fn new(
+        model: WatchModel,
+        settings: WatchSettings,
+        time_manager: TimeManager,
+        peripherals: Peripherals,
+    ) -> Result<Self> {
+        let mut settings = settings;
+        fit_alarm_slots(model, &mut settings);
+        let keymap = Keymap::new(model, &settings.keymap)?;
+        let theme = Theme::load(&settings.theme)?;
+        let countdown_minutes = settings.countdown_minutes;
+        let settings_countdown_ms = countdown_minutes * 60 * 1000;
 
-        let (mode, f91w_mode) = match model {
-            WatchModel::AE1200 => (WatchMode::Home, F91WMode::Time),
-            WatchModel::F91W => (WatchMode::Home, F91WMode::Time),
+        // don't go off straight away if we start on the alarm minute or the hour
+        let now = time_manager.current_time.naive_local();
+        let last_alarm = now.with_second(0).and_then(|time| time.with_nanosecond(0));
+        let last_signal = last_alarm.and_then(|time| time.with_minute(0));
+        let scripts = match model.definition() {
+            Some(definition) => definition
+                .modes
+                .iter()
+                .map(|mode| {
+                    mode.script
+                        .as_deref()
+                        .map(|path| Script::load(path, time_manager.current_time))
+                        .transpose()
+                })
+                .collect::<Result<_>>()?,
+            None => Vec::new(),
         };
 
         Ok(Self {
             model,
-            mode,
-            f91w_mode,
+            mode: WatchMode::Home,
+            f91w_mode: F91WMode::Time,
+            ca53w_mode: CA53WMode::Time,
+            dw5600_mode: DW5600Mode::Time,
+            gwm5610_mode: GWM5610Mode::Time,
+            db36_mode: DB36Mode::Time,
+            aq230_mode: AQ230Mode::Time,
+            prg240_mode: PRG240Mode::Time,
+            w800h_mode: W800HMode::Time,
+            glx5600_mode: GLX5600Mode::Time,
+            almanac_offset: TimeDelta::zero(),
+            cmd40_mode: CMD40Mode::Time,
+            ir_device: 0,
+            ir_code: 0,
+            vdb1000_mode: VDB1000Mode::Time,
+            grid: Grid::default(),
+            memo_index: 0,
+            scheduler: Scheduler::default(),
+            gd8_mode: GD8Mode::Time,
+            game: Game::new(),
+            custom_mode: 0,
+            scripts,
+            mcu_started: time_manager.current_time,
+            alarm_slot: 0,
+            calculator: Calculator::new(),
+            telememo: Telememo::default(),
+            hands: Hands::new(settings.hand_offset),
+            calibrating: false,
+            theme_choice: settings.theme.clone(),
             settings,
+            keymap,
+            theme,
             time_manager,
+            peripherals,
             stopwatch_time: 0,
             stopwatch_running: false,
             stopwatch_start_time: None,
+            stopwatch_split: None,
             timer_time: 0,
             timer_running: false,
             timer_start_time: None,
+            countdown_remaining: settings_countdown_ms,
+            countdown_running: false,
+            countdown_start_time: None,
+            countdown_started_from: settings_countdown_ms,
+            countdown_set_to: countdown_minutes,
+            countdown_beep_start_time: None,
             light_on: false,
             light_start_time: None,
+            alarm_start_time: None,
+            signal_start_time: None,
+            last_alarm,
+            last_signal,
+            last_auto_reception: last_signal,
         })
     }
 
@@ -73,149 +1280,885 @@ impl Watch {
         self.time_manager.update();
 
         // update stopwatch if running
-        if self.stopwatch_running {
-            if let Some(start_time) = self.stopwatch_start_time {
-                let elapsed = start_time.elapsed().as_millis() as u64;
-                self.stopwatch_time = elapsed;
-            }
+        if self.stopwatch_running
+            && let Some(start_time) = self.stopwatch_start_time
+        {
+            let elapsed = start_time.elapsed().as_millis() as u64;
+            self.stopwatch_time = elapsed % STOPWATCH_RANGE_MS;
         }
 
+        self.update_countdown();
+
         // update timer if running
-        if self.timer_running {
-            if let Some(start_time) = self.timer_start_time {
-                let elapsed = start_time.elapsed().as_millis() as u64;
-                self.timer_time = elapsed;
-            }
+        if self.timer_running
+            && let Some(start_time) = self.timer_start_time
+        {
+            let elapsed = start_time.elapsed().as_millis() as u64;
+            self.timer_time = elapsed;
         }
 
         // turn the light off
-        if self.light_on {
-            if let Some(start_time) = self.light_start_time {
-                let elapsed = start_time.elapsed().as_secs();
-                if elapsed >= self.settings.auto_light_duration {
-                    self.light_on = false;
-                    self.light_start_time = None;
+        if self.light_on
+            && let Some(start_time) = self.light_start_time
+        {
+            let elapsed = start_time.elapsed().as_secs();
+            if elapsed >= self.settings.auto_light_duration {
+                self.light_on = false;
+                self.light_start_time = None;
+            }
+        }
+
+        // an analog-only movement has no buzzer, and firmware does its own alarms
+        if !matches!(self.model.module(), Module::MQ24 | Module::Firmware) {
+            self.update_alarm();
+        }
+
+        match self.model.module() {
+            Module::GWM5610 => self.update_reception(),
+            Module::PRG240 => {
+                if let Some(sensors) = self.peripherals.sensors_mut() {
+                    sensors.update(self.time_manager.current_time, &self.settings.calibration);
                 }
             }
+            Module::GD8 => self.update_game()?,
+            Module::Custom => self.update_scripts(),
+            Module::Firmware => self.update_mcu(),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // nightly reception on the hour until one works, then feeds the receiver
+    fn update_reception(&mut self) {
+        let Some(receiver) = self.peripherals.receiver_mut() else {
+            return;
+        };
+        let now = self.time_manager.current_time;
+        let hour = now
+            .naive_local()
+            .with_minute(0)
+            .and_then(|time| time.with_second(0))
+            .and_then(|time| time.with_nanosecond(0));
+        if !receiver.receiving()
+            && now.hour() <= LAST_AUTO_RECEPTION_HOUR
+            && now.minute() == 0
+            && self.last_auto_reception != hour
+            && !receiver.received_on(now.date_naive())
+        {
+            self.last_auto_reception = hour;
+            receiver.start(now);
+        }
+
+        if let Some(time) = receiver.update(now) {
+            self.time_manager.correct_to(time);
+            // don't let the jump set off the alarm or the signal a second time
+            let minute = time
+                .naive_local()
+                .with_second(0)
+                .and_then(|time| time.with_nanosecond(0));
+            self.last_alarm = self.last_alarm.max(minute);
+            self.last_signal = self
+                .last_signal
+                .max(minute.and_then(|time| time.with_minute(0)));
+            self.last_auto_reception = self.last_signal;
+        }
+    }
+
+    // counts down to zero, then beeps and either stops or starts over
+    fn update_countdown(&mut self) {
+        if self.countdown_running
+            && let Some(start_time) = self.countdown_start_time
+        {
+            let elapsed = start_time.elapsed().as_millis() as u64;
+            if elapsed >= self.countdown_started_from {
+                self.countdown_beep_start_time = Some(Instant::now());
+                let duration = self.countdown_duration();
+                if self.settings.countdown_auto_repeat {
+                    // carry the overshoot into the next round so repeats don't drift
+                    let overshoot = elapsed - self.countdown_started_from;
+                    self.countdown_started_from = duration;
+                    self.countdown_start_time = Instant::now()
+                        .checked_sub(Duration::from_millis(overshoot))
+                        .or(Some(Instant::now()));
+                    self.countdown_remaining = duration.saturating_sub(overshoot);
+                } else {
+                    self.countdown_running = false;
+                    self.countdown_start_time = None;
+                    self.countdown_remaining = duration;
+                }
+            } else {
+                self.countdown_remaining = self.countdown_started_from - elapsed;
+            }
+        }
+
+        if self
+            .countdown_beep_start_time
+            .is_some_and(|start| start.elapsed().as_secs() >= COUNTDOWN_BEEP_SECS)
+        {
+            self.countdown_beep_start_time = None;
+        }
+    }
+
+    fn countdown_duration(&self) -> u64 {
+        self.settings.countdown_minutes * 60 * 1000
+    }
+
+    // daily alarm and hourly time signal, shared by every module
+    fn update_alarm(&mut self) {
+        let now = self.time_manager.current_time.naive_local();
+
+        let minute = now.with_second(0).and_then(|time| time.with_nanosecond(0));
+        let due = self
+            .daily_alarms()
+            .iter()
+            .any(|alarm| alarm.enabled && self.time_manager.check_alarm(&alarm.time))
+            || (self.model.module() == Module::VDB1000
+                && self.settings.schedule.iter().any(|entry| entry.due(now)));
+        if due && self.last_alarm != minute {
+            self.last_alarm = minute;
+            self.alarm_start_time = Some(Instant::now());
+        }
+
+        let hour = minute.and_then(|time| time.with_minute(0));
+        if self.settings.hourly_signal && now.minute() == 0 && self.last_signal != hour {
+            self.last_signal = hour;
+            self.signal_start_time = Some(Instant::now());
+        }
+
+        if self
+            .alarm_start_time
+            .is_some_and(|start| start.elapsed().as_secs() >= ALARM_DURATION_SECS)
+        {
+            self.alarm_start_time = None;
+        }
+        if self
+            .signal_start_time
+            .is_some_and(|start| start.elapsed().as_secs() >= SIGNAL_DURATION_SECS)
+        {
+            self.signal_start_time = None;
+        }
+    }
+
+    // steps the game and keeps the best score once a game ends
+    fn update_game(&mut self) -> Result<()> {
+        self.game.update(Instant::now());
+        if self.game.over && self.game.score > self.settings.game_high_score {
+            self.settings.game_high_score = self.game.score;
+            self.settings.save()?;
+        }
+        Ok(())
+    }
+
+    // the mcu runs as many instructions as its crystal would have clocked in the watch time gone by
+    fn update_mcu(&mut self) {
+        let Some(mcu) = self.peripherals.mcu_mut() else {
+            return;
+        };
+        let now = self.time_manager.current_time;
+        let run = TimeDelta::milliseconds((mcu.cycles() * 1000 / mcu::CLOCK_HZ) as i64);
+        // when the time is set back, the crystal carries on from where it was
+        if now - self.mcu_started < run {
+            self.mcu_started = now - run;
+        }
+        let elapsed = (now - self.mcu_started).num_milliseconds() as u64;
+        let cycles = (elapsed * mcu::CLOCK_HZ / 1000).saturating_sub(mcu.cycles());
+        // after a long stall it catches up a bit at a time rather than freezing the screen
+        mcu.run(cycles.min(MCU_MAX_CYCLES_PER_UPDATE));
+        if mcu.buzzer {
+            self.signal_start_time = Some(Instant::now());
+        }
+    }
+
+    fn press_mcu(&mut self, button: Button) {
+        if let Some(mcu) = self.peripherals.mcu_mut() {
+            mcu.press(button);
+        }
+    }
+
+    // every script ticks, even in the background, but only the one on show redraws
+    fn update_scripts(&mut self) {
+        let now = self.time_manager.current_time;
+        for (index, script) in self.scripts.iter_mut().enumerate() {
+            let Some(script) = script else {
+                continue;
+            };
+            script.tick(now);
+            if index == self.custom_mode {
+                script.draw(now);
+            }
+            if script.take_beep() {
+                self.alarm_start_time = Some(Instant::now());
+            }
         }
+    }
+
+    fn press_script_button(&mut self, button: Button) {
+        let now = self.time_manager.current_time;
+        if let Some(Some(script)) = self.scripts.get_mut(self.custom_mode) {
+            script.button(button.name(), now);
+        }
+    }
 
-        // todo: handle alarm, beeping sound etc
-        if self.settings.alarm_enabled && self.time_manager.check_alarm(&self.settings.alarm_time) {
-            todo!()
+    // the script on show comes first, then any other with something to say
+    pub fn script_status(&self) -> Option<&str> {
+        let current = self.scripts.get(self.custom_mode).into_iter();
+        current
+            .chain(&self.scripts)
+            .flatten()
+            .find_map(|script| script.status.as_deref())
+    }
+
+    // the keys on the touch screen in the current mode, row by row
+    pub fn touch_keys(&self) -> Vec<Vec<TouchKey>> {
+        if self.model.module() != Module::VDB1000 {
+            return Vec::new();
         }
+        let calc = |keys: [CalcKey; 4]| keys.map(TouchKey::Calc).to_vec();
+        let cells = |row: usize| (row * 3..row * 3 + 3).map(TouchKey::Cell);
+        match self.vdb1000_mode {
+            VDB1000Mode::Time => Vec::new(),
+            VDB1000Mode::Calculator => vec![
+                calc([
+                    CalcKey::Digit(7),
+                    CalcKey::Digit(8),
+                    CalcKey::Digit(9),
+                    CalcKey::Divide,
+                ]),
+                calc([
+                    CalcKey::Digit(4),
+                    CalcKey::Digit(5),
+                    CalcKey::Digit(6),
+                    CalcKey::Multiply,
+                ]),
+                calc([
+                    CalcKey::Digit(1),
+                    CalcKey::Digit(2),
+                    CalcKey::Digit(3),
+                    CalcKey::Subtract,
+                ]),
+                calc([
+                    CalcKey::Digit(0),
+                    CalcKey::Point,
+                    CalcKey::Equals,
+                    CalcKey::Add,
+                ]),
+                vec![TouchKey::ClearCalc],
+            ],
+            VDB1000Mode::Memo => vec![
+                cells(0).chain([TouchKey::Write]).collect(),
+                cells(1).chain([TouchKey::Space]).collect(),
+                cells(2).chain([TouchKey::Delete]).collect(),
+                vec![TouchKey::Erase, TouchKey::NextMemo],
+            ],
+            VDB1000Mode::Schedule => vec![
+                vec![
+                    TouchKey::Previous,
+                    TouchKey::Next,
+                    TouchKey::Add,
+                    TouchKey::Remove,
+                ],
+                vec![TouchKey::Shift(-1440), TouchKey::Shift(1440)],
+                vec![TouchKey::Shift(-60), TouchKey::Shift(60)],
+                vec![TouchKey::Shift(-10), TouchKey::Shift(10)],
+            ],
+        }
+    }
 
+    pub fn touch(&mut self, key: TouchKey) -> Result<()> {
+        // a touch silences the alarm like a button does
+        if self.alarm_ringing() {
+            self.alarm_start_time = None;
+            return Ok(());
+        }
+
+        let settings = &mut self.settings;
+        match key {
+            TouchKey::Calc(key) => self.calculator.press(key),
+            TouchKey::ClearCalc => self.calculator.clear(),
+            TouchKey::Cell(cell) => self.grid.touch(cell),
+            TouchKey::Erase => self.grid.erase(),
+            TouchKey::Write => {
+                if let Some(letter) = self.grid.recognise() {
+                    self.grid.erase();
+                    return self.type_memo(Some(letter));
+                }
+            }
+            TouchKey::Space => return self.type_memo(Some(' ')),
+            TouchKey::Delete => return self.type_memo(None),
+            TouchKey::NextMemo => {
+                self.grid.erase();
+                self.memo_index =
+                    (self.memo_index.min(settings.memos.len()) + 1) % (settings.memos.len() + 1);
+            }
+            TouchKey::Previous => self.scheduler.previous(&settings.schedule),
+            TouchKey::Next => self.scheduler.next(&settings.schedule),
+            // a new appointment starts at the next hour, named after the memo being written
+            TouchKey::Add => {
+                let now = self.time_manager.current_time.naive_local();
+                let hour = now.with_minute(0).and_then(|time| time.with_second(0));
+                let text = settings
+                    .memos
+                    .get(self.memo_index)
+                    .map(String::as_str)
+                    .unwrap_or("APPOINTMENT");
+                if let Some(hour) = hour {
+                    let appointment = Appointment::new(hour + TimeDelta::hours(1), text);
+                    self.scheduler.add(&mut settings.schedule, appointment);
+                    return settings.save();
+                }
+            }
+            TouchKey::Remove => {
+                self.scheduler.remove(&mut settings.schedule);
+                return settings.save();
+            }
+            TouchKey::Shift(minutes) => {
+                self.scheduler.shift(&mut settings.schedule, minutes);
+                return settings.save();
+            }
+        }
         Ok(())
     }
 
-    pub fn toggle_mode(&mut self) -> Result<()> {
-        match self.model {
-            WatchModel::AE1200 => {
-                self.mode = match self.mode {
-                    WatchMode::Home => WatchMode::WorldTime,
-                    WatchMode::WorldTime => WatchMode::Alarm,
-                    WatchMode::Alarm => WatchMode::Timer,
-                    WatchMode::Timer => WatchMode::Stopwatch,
-                    WatchMode::Stopwatch => WatchMode::Home,
-                };
+    // adds a character to the memo being written, or takes the last one off
+    fn type_memo(&mut self, letter: Option<char>) -> Result<()> {
+        let memos = &mut self.settings.memos;
+        if self.memo_index >= memos.len() {
+            if letter.is_none() || memos.len() >= databank::MEMOS {
+                return Ok(());
             }
-            WatchModel::F91W => {
-                self.f91w_mode = match self.f91w_mode {
-                    F91WMode::Time => F91WMode::Alarm,
-                    F91WMode::Alarm => F91WMode::Stopwatch,
-                    F91WMode::Stopwatch => F91WMode::Time,
-                };
+            memos.push(String::new());
+            self.memo_index = memos.len() - 1;
+        }
+        let memo = &mut memos[self.memo_index];
+        match letter {
+            Some(letter) if memo.chars().count() < databank::MEMO_LENGTH => memo.push(letter),
+            Some(_) => {}
+            None => {
+                memo.pop();
             }
         }
+        // a page with nothing left on it goes away
+        if memo.trim().is_empty() && letter.is_none() {
+            memos.remove(self.memo_index);
+        }
+        self.settings.save()
+    }
+
+    // the device and code the remote would send now
+    pub fn ir_selection(&self) -> Option<(Device, IrCode)> {
+        let device = self.settings.ir_devices.get(self.ir_device)?;
+        let code = device.codes.get(self.ir_code)?;
+        Some((device.clone(), code.clone()))
+    }
+
+    // adds the code shown in learn mode to the learned device, replacing one with the same name
+    fn store_learned(&mut self) -> Result<()> {
+        let Some(learned) = self.peripherals.remote().and_then(Remote::current_learned) else {
+            return Ok(());
+        };
+        let Some((protocol, address, command)) = learned.code else {
+            return Ok(());
+        };
+        let code = IrCode {
+            name: learned.name.clone(),
+            protocol,
+            address,
+            command,
+        };
+
+        let devices = &mut self.settings.ir_devices;
+        let index = match devices
+            .iter()
+            .position(|device| device.name == ir::LEARNED_DEVICE)
+        {
+            Some(index) => index,
+            None => {
+                devices.push(Device {
+                    name: ir::LEARNED_DEVICE.to_string(),
+                    codes: Vec::new(),
+                });
+                devices.len() - 1
+            }
+        };
+        let codes = &mut devices[index].codes;
+        match codes.iter_mut().find(|existing| existing.name == code.name) {
+            Some(existing) => *existing = code,
+            None => codes.push(code),
+        }
+        self.settings.save()
+    }
+
+    // the chosen port, with the establishment from the settings if one is set for it
+    pub fn tide_port(&self) -> Port {
+        let port = Port::find(&self.settings.tide_port);
+        Port {
+            establishment: self
+                .settings
+                .tide_establishments
+                .get(port.code)
+                .copied()
+                .unwrap_or(port.establishment),
+            ..port
+        }
+    }
+
+    // the settings' main alarm, followed by any extra ones this model has
+    pub fn daily_alarms(&self) -> Vec<DailyAlarm> {
+        let main = DailyAlarm {
+            enabled: self.settings.alarm_enabled,
+            time: self.settings.alarm_time.clone(),
+        };
+        let extra = match self.model.module() {
+            Module::W800H => &self.settings.extra_alarms[..],
+            _ => &[],
+        };
+        std::iter::once(main).chain(extra.iter().cloned()).collect()
+    }
+
+    // what the current mode does on a model from a definition file
+    pub fn custom_function(&self) -> Option<Function> {
+        self.model
+            .definition()
+            .map(|definition| definition.modes[self.custom_mode].function)
+    }
+
+    pub fn alarm_ringing(&self) -> bool {
+        self.alarm_start_time.is_some()
+    }
+
+    // picks up changes made to the settings while running
+    pub fn apply_settings(&mut self) -> Result<()> {
+        fit_alarm_slots(self.model, &mut self.settings);
+        self.keymap = Keymap::new(self.model, &self.settings.keymap)?;
+        // the theme only comes off disk when the choice changes; the menu checks a file before
+        // storing it, so a broken one here came with another profile and gets the default
+        if self.theme_choice != self.settings.theme {
+            self.theme = Theme::load(&self.settings.theme).unwrap_or_default();
+            self.theme_choice = self.settings.theme.clone();
+        }
+        // a new start time resets a stopped countdown, the same as setting it on the watch
+        if self.countdown_set_to != self.settings.countdown_minutes {
+            self.countdown_set_to = self.settings.countdown_minutes;
+            if !self.countdown_running {
+                self.countdown_remaining = self.countdown_duration();
+            }
+        }
+        // hands being set keep going until the crown goes back in and saves them
+        if !self.hands.crown_pulled() {
+            self.hands = Hands::new(self.settings.hand_offset);
+        }
         Ok(())
     }
 
-    pub fn toggle_start_stop(&mut self) -> Result<()> {
-        match self.model {
-            WatchModel::AE1200 => {
-                match self.mode {
-                    WatchMode::Stopwatch => {
-                        if self.stopwatch_running {
-                            // Stop the stopwatch
-                            self.stopwatch_running = false;
-                            if let Some(start_time) = self.stopwatch_start_time {
-                                let elapsed = start_time.elapsed().as_millis() as u64;
-                                self.stopwatch_time = elapsed;
-                            }
-                            self.stopwatch_start_time = None;
-                        } else {
-                            self.stopwatch_running = true;
-                            self.stopwatch_start_time = Some(Instant::now());
-                        }
-                    }
-                    WatchMode::Timer => {
-                        if self.timer_running {
-                            // Start the stopwatch
-                            self.timer_running = false;
-                            if let Some(start_time) = self.timer_start_time {
-                                let elapsed = start_time.elapsed().as_millis() as u64;
-                                self.timer_time = elapsed;
-                            }
-                            self.timer_start_time = None;
-                        } else {
-                            self.timer_running = true;
-                            self.timer_start_time = Some(Instant::now());
-                        }
+    pub fn press(&mut self, button: Button) -> Result<()> {
+        // any button silences a sounding alarm and does nothing else
+        if self.alarm_ringing() || self.countdown_beep_start_time.is_some() {
+            self.alarm_start_time = None;
+            self.countdown_beep_start_time = None;
+            return Ok(());
+        }
+
+        // the keypad keys aren't in the tables, they only ever work the calculator
+        if let Button::Calc(key) = button {
+            if self.model.module() == Module::CA53W && self.ca53w_mode == CA53WMode::Calculator {
+                self.calculator.press(key);
+            }
+            return Ok(());
+        }
+
+        // a button missing from the table does nothing in this mode
+        match self
+            .controls()
+            .into_iter()
+            .find(|(other, _)| *other == button)
+        {
+            Some((_, control)) => self.perform(control, button),
+            None => Ok(()),
+        }
+    }
+
+    // a line of the lcd telling what the buttons do right now, from the same table press goes by;
+    // a button with nothing to do is left out
+    pub fn hint(&self, buttons: &[Button]) -> String {
+        let controls = self.controls();
+        let entries: Vec<(Button, &str)> = buttons
+            .iter()
+            .filter_map(|button| {
+                controls
+                    .iter()
+                    .find(|(other, _)| other == button)
+                    .map(|(_, control)| (*button, control.hint(*button)))
+            })
+            .collect();
+        self.keymap.hint(&entries)
+    }
+
+    // what each button does right now, for press and the manual alike
+    pub fn controls(&self) -> Vec<(Button, Control)> {
+        let mode = match self.model.module() {
+            Module::AE1200 => self.mode.controls(),
+            Module::F91W => self.f91w_mode.controls(),
+            Module::CA53W => self.ca53w_mode.controls(),
+            Module::DW5600 => self.dw5600_mode.controls(),
+            Module::GWM5610 => self.gwm5610_mode.controls(),
+            Module::DB36 if self.telememo.editor.is_some() => TELEMEMO_INPUT_CONTROLS,
+            Module::DB36 => self.db36_mode.controls(),
+            Module::AQ230 => self.aq230_mode.controls(),
+            Module::PRG240 if self.calibrating => CALIBRATION_CONTROLS,
+            Module::PRG240 => self.prg240_mode.controls(),
+            Module::W800H
+                if self.w800h_mode == W800HMode::Alarm && self.alarm_slot == W800H_ALARMS =>
+            {
+                W800H_SIGNAL_CONTROLS
+            }
+            Module::W800H => self.w800h_mode.controls(),
+            Module::GLX5600 => self.glx5600_mode.controls(),
+            Module::CMD40 => self.cmd40_mode.controls(),
+            Module::VDB1000 => self.vdb1000_mode.controls(),
+            Module::GD8 => self.gd8_mode.controls(),
+            Module::MQ24 => &[],
+            Module::Firmware => FIRMWARE_CONTROLS,
+            Module::Custom => self.custom_function().map_or(&[][..], Function::controls),
+        };
+
+        let mut controls = Vec::new();
+        // telememo input and calibration take the mode button over too
+        let cycles = match self.model.module() {
+            Module::MQ24 | Module::Firmware => false,
+            Module::DB36 => self.telememo.editor.is_none(),
+            Module::PRG240 => !self.calibrating,
+            Module::Custom => self.model.definition().is_some(),
+            _ => true,
+        };
+        if cycles {
+            controls.push((Button::Mode, Control::NextMode));
+        }
+        controls.extend_from_slice(mode);
+
+        if matches!(self.model.module(), Module::AQ230 | Module::MQ24) {
+            if self.hands.crown_pulled() {
+                controls.extend_from_slice(CROWN_PULLED_CONTROLS);
+            } else {
+                controls.push((Button::CrownPull, Control::PullCrown));
+            }
+        }
+        if self.model.light() != Light::None {
+            controls.push((Button::Light, Control::Light));
+        }
+        controls
+    }
+
+    fn perform(&mut self, control: Control, button: Button) -> Result<()> {
+        match control {
+            Control::NextMode => self.next_mode(),
+            Control::Light => self.toggle_light()?,
+            Control::AlarmTest => self.set_alarm()?,
+            Control::AlarmSignal => self.cycle_alarm_signal()?,
+            // in alarm mode the w-800h steps through alarms 1-3 and the hourly signal
+            Control::AlarmSlot => self.toggle_alarm_slot()?,
+            Control::NextAlarmSlot => self.alarm_slot = (self.alarm_slot + 1) % (W800H_ALARMS + 1),
+            Control::StopwatchStartStop => self.start_stop_stopwatch(),
+            Control::StopwatchReset => self.reset_stopwatch(),
+            Control::StopwatchSplitReset => self.split_reset_stopwatch(),
+            Control::TimerStartStop => self.start_stop_timer(),
+            Control::TimerReset => {
+                self.timer_time = 0;
+                self.timer_running = false;
+                self.timer_start_time = None;
+            }
+            Control::CountdownStartStop => self.start_stop_countdown(),
+            Control::CountdownReset => self.reset_countdown()?,
+            Control::CountdownStart => self.advance_countdown_start()?,
+            Control::DualTimeForward => self.advance_dual_time()?,
+            Control::DualTimeHome => self.reset_dual_time()?,
+            Control::CalculatorClear => self.calculator.clear(),
+            // starts a manual reception, or cancels one
+            Control::Reception => {
+                if let Some(receiver) = self.peripherals.receiver_mut() {
+                    if receiver.receiving() {
+                        receiver.cancel();
+                    } else {
+                        receiver.start(self.time_manager.current_time);
                     }
-                    _ => {}
                 }
             }
-            WatchModel::F91W => match self.f91w_mode {
-                F91WMode::Stopwatch => {
-                    if self.stopwatch_running {
-                        if let Some(start_time) = self.timer_start_time {
-                            self.stopwatch_running = false;
-                            if let Some(start_time) = self.stopwatch_start_time {
-                                let elapsed = start_time.elapsed().as_millis() as u64;
-                                self.stopwatch_time = elapsed;
-                            }
-                            self.stopwatch_start_time = None;
-                        } else {
-                            self.stopwatch_running = true;
-                            self.stopwatch_start_time = Some(Instant::now());
-                        }
+            Control::NextRecord => self.telememo.next(&self.settings.telememo),
+            Control::PreviousRecord => self.telememo.previous(&self.settings.telememo),
+            Control::EditRecord => self.telememo.edit(&self.settings.telememo),
+            Control::NextCharacter | Control::PreviousCharacter => {
+                if let Some(editor) = &mut self.telememo.editor {
+                    editor.step(control == Control::NextCharacter);
+                }
+            }
+            Control::NextPosition => {
+                if let Some(editor) = &mut self.telememo.editor {
+                    editor.advance();
+                }
+            }
+            Control::StoreRecord => {
+                self.telememo.commit(&mut self.settings.telememo);
+                self.settings.save()?;
+            }
+            Control::BearingMemory | Control::ReferenceAltitude => {
+                let calibration = &self.settings.calibration;
+                if let Some(sensors) = self.peripherals.sensors_mut() {
+                    match control {
+                        Control::BearingMemory => sensors.toggle_bearing_memory(calibration),
+                        _ => sensors.toggle_altitude_reference(calibration),
                     }
                 }
-                _ => {}
-            },
-            _ => {}
+            }
+            Control::Calibrate => self.calibrating = true,
+            Control::CalibrationUp => self.adjust_calibration(1.0),
+            Control::CalibrationDown => self.adjust_calibration(-1.0),
+            Control::SaveCalibration => {
+                self.calibrating = false;
+                self.settings.save()?;
+            }
+            // tide and moon modes look ahead an hour or a day at a time
+            Control::TideForward => self.almanac_offset += TimeDelta::hours(1),
+            Control::MoonForward => self.almanac_offset += TimeDelta::days(1),
+            Control::TideNow | Control::MoonToday => self.almanac_offset = TimeDelta::zero(),
+            Control::NextPort => {
+                self.settings.tide_port = self.tide_port().next().code.to_string();
+                self.settings.save()?;
+            }
+            Control::SendCode => {
+                if let Some((device, code)) = self.ir_selection()
+                    && let Some(remote) = self.peripherals.remote_mut()
+                {
+                    remote.send(&device.name, &code);
+                }
+            }
+            Control::NextKey => {
+                if let Some(device) = self.settings.ir_devices.get(self.ir_device) {
+                    self.ir_code = (self.ir_code + 1) % device.codes.len().max(1);
+                }
+            }
+            Control::NextDevice => {
+                self.ir_device = (self.ir_device + 1) % self.settings.ir_devices.len().max(1);
+                self.ir_code = 0;
+            }
+            Control::NextLearned => {
+                if let Some(remote) = self.peripherals.remote_mut() {
+                    remote.next_learned();
+                }
+            }
+            Control::StoreCode => self.store_learned()?,
+            Control::EraseGrid => self.grid.erase(),
+            Control::SteerLeft => self.game.steer(false),
+            Control::SteerRight => self.game.steer(true),
+            // in the game it starts, pauses and restarts
+            Control::StartPause => self.game.start_pause(),
+            Control::PullCrown | Control::PushCrown => self.pull_crown()?,
+            Control::HandsForward => self.hands.turn_crown(1),
+            Control::HandsBack => self.hands.turn_crown(-1),
+            Control::Rom => self.press_mcu(button),
+            Control::Script => self.press_script_button(button),
         }
+        Ok(())
+    }
 
+    // one key for several buttons, pressed one after another rather than held together
+    pub fn press_sequence(&mut self, buttons: &[Button]) -> Result<()> {
+        for button in buttons {
+            self.press(*button)?;
+        }
         Ok(())
     }
 
-    pub fn reset(&mut self) -> Result<()> {
-        match self.model {
-            WatchModel::AE1200 => match self.mode {
-                WatchMode::Stopwatch => {
-                    self.stopwatch_time = 0;
-                    self.stopwatch_running = false;
-                    self.stopwatch_start_time = None;
-                }
-                WatchMode::Timer => {
-                    self.timer_time = 0;
-                    self.timer_running = false;
-                    self.timer_start_time = None;
+    fn next_mode(&mut self) {
+        match self.model.module() {
+            Module::AE1200 => self.mode = self.mode.next(),
+            Module::F91W => self.f91w_mode = self.f91w_mode.next(),
+            Module::CA53W => self.ca53w_mode = self.ca53w_mode.next(),
+            Module::DW5600 => self.dw5600_mode = self.dw5600_mode.next(),
+            Module::GWM5610 => self.gwm5610_mode = self.gwm5610_mode.next(),
+            Module::DB36 => self.db36_mode = self.db36_mode.next(),
+            Module::AQ230 => self.aq230_mode = self.aq230_mode.next(),
+            Module::MQ24 | Module::Firmware => {}
+            Module::Custom => {
+                if let Some(definition) = self.model.definition() {
+                    self.custom_mode = definition.next_mode(self.custom_mode);
                 }
-                _ => {}
-            },
-            WatchModel::F91W => match self.f91w_mode {
-                F91WMode::Stopwatch => {
-                    self.stopwatch_time = 0;
-                    self.stopwatch_running = false;
-                    self.stopwatch_start_time = None;
+            }
+            // leaving the game pauses it
+            Module::GD8 => {
+                if self.game.running {
+                    self.game.start_pause();
                 }
-                _ => {}
+                self.gd8_mode = self.gd8_mode.next();
+            }
+            Module::VDB1000 => {
+                self.grid.erase();
+                self.vdb1000_mode = self.vdb1000_mode.next();
+            }
+            Module::CMD40 => self.cmd40_mode = self.cmd40_mode.next(),
+            Module::GLX5600 => {
+                self.almanac_offset = TimeDelta::zero();
+                self.glx5600_mode = self.glx5600_mode.next();
+            }
+            Module::PRG240 => self.prg240_mode = self.prg240_mode.next(),
+            Module::W800H => self.w800h_mode = self.w800h_mode.next(),
+        }
+    }
+
+    fn start_stop_timer(&mut self) {
+        if self.timer_running {
+            self.timer_running = false;
+            if let Some(start_time) = self.timer_start_time {
+                let elapsed = start_time.elapsed().as_millis() as u64;
+                self.timer_time = elapsed;
+            }
+            self.timer_start_time = None;
+        } else {
+            self.timer_running = true;
+            self.timer_start_time = Some(Instant::now());
+        }
+    }
+
+    fn start_stop_countdown(&mut self) {
+        if self.countdown_running {
+            self.countdown_running = false;
+            self.countdown_start_time = None;
+        } else {
+            self.countdown_running = true;
+            self.countdown_started_from = self.countdown_remaining;
+            self.countdown_start_time = Some(Instant::now());
+        }
+    }
+
+    // only while stopped: back to the start time, or toggle auto-repeat if already there
+    fn reset_countdown(&mut self) -> Result<()> {
+        if self.countdown_running {
+            return Ok(());
+        }
+        let duration = self.countdown_duration();
+        if self.countdown_remaining == duration {
+            self.settings.countdown_auto_repeat = !self.settings.countdown_auto_repeat;
+            self.settings.save()?;
+        }
+        self.countdown_remaining = duration;
+        Ok(())
+    }
+
+    // steps the start time one minute at a time, from 1 to 60 minutes
+    fn advance_countdown_start(&mut self) -> Result<()> {
+        if self.countdown_running {
+            return Ok(());
+        }
+        self.settings.countdown_minutes = self.settings.countdown_minutes % 60 + 1;
+        self.settings.save()?;
+        self.countdown_set_to = self.settings.countdown_minutes;
+        self.countdown_remaining = self.countdown_duration();
+        Ok(())
+    }
+
+    fn start_stop_stopwatch(&mut self) {
+        if self.stopwatch_running {
+            self.stopwatch_running = false;
+            if let Some(start_time) = self.stopwatch_start_time {
+                let elapsed = start_time.elapsed().as_millis() as u64;
+                self.stopwatch_time = elapsed % STOPWATCH_RANGE_MS;
+            }
+            self.stopwatch_start_time = None;
+        } else {
+            // carry on from the stopped reading rather than from zero
+            self.stopwatch_running = true;
+            self.stopwatch_start_time = Instant::now()
+                .checked_sub(Duration::from_millis(self.stopwatch_time))
+                .or(Some(Instant::now()));
+        }
+    }
+
+    fn reset_stopwatch(&mut self) {
+        self.stopwatch_time = 0;
+        self.stopwatch_running = false;
+        self.stopwatch_start_time = None;
+        self.stopwatch_split = None;
+    }
+
+    // while running this freezes and releases a split reading; stopped, it clears
+    fn split_reset_stopwatch(&mut self) {
+        if self.stopwatch_split.is_some() {
+            self.stopwatch_split = None;
+        } else if self.stopwatch_running {
+            self.stopwatch_split = Some(self.stopwatch_time);
+        } else {
+            self.reset_stopwatch();
+        }
+    }
+
+    // turns the alarm shown in alarm mode on or off
+    fn toggle_alarm_slot(&mut self) -> Result<()> {
+        let settings = &mut self.settings;
+        match self.alarm_slot {
+            0 => settings.alarm_enabled = !settings.alarm_enabled,
+            slot => match settings.extra_alarms.get_mut(slot - 1) {
+                Some(alarm) => alarm.enabled = !alarm.enabled,
+                None => settings.hourly_signal = !settings.hourly_signal,
             },
         }
+        settings.save()
+    }
+
+    // start/stop in alarm mode steps through alarm only, signal only, both, neither
+    fn cycle_alarm_signal(&mut self) -> Result<()> {
+        let settings = &mut self.settings;
+        (settings.alarm_enabled, settings.hourly_signal) =
+            match (settings.alarm_enabled, settings.hourly_signal) {
+                (false, false) => (true, false),
+                (true, false) => (false, true),
+                (false, true) => (true, true),
+                (true, true) => (false, false),
+            };
+        settings.save()
+    }
+
+    // the second time zone is set in whole hours
+    fn advance_dual_time(&mut self) -> Result<()> {
+        self.settings.dual_time_offset = (self.settings.dual_time_offset + 60).rem_euclid(24 * 60);
+        self.settings.save()
+    }
+
+    fn reset_dual_time(&mut self) -> Result<()> {
+        self.settings.dual_time_offset = 0;
+        self.settings.save()
+    }
+
+    // one step is a degree, 5 m, 1 hPa or 0.1 °C depending on the sensor
+    fn adjust_calibration(&mut self, steps: f64) {
+        let calibration = &mut self.settings.calibration;
+        match self.prg240_mode {
+            PRG240Mode::Compass => {
+                calibration.heading = (calibration.heading + steps).rem_euclid(360.0);
+            }
+            PRG240Mode::Altimeter => calibration.altitude += steps * 5.0,
+            PRG240Mode::Barometer => calibration.pressure += steps,
+            PRG240Mode::Thermometer => {
+                // keep tenths exact so repeated steps don't drift
+                calibration.temperature =
+                    ((calibration.temperature + steps * 0.1) * 10.0).round() / 10.0;
+            }
+            PRG240Mode::Time => {}
+        }
+    }
+
+    // pushing the crown back in keeps where the hands were set to
+    fn pull_crown(&mut self) -> Result<()> {
+        self.hands.pull_crown(self.time_manager.current_time);
+        if !self.hands.crown_pulled() {
+            self.settings.hand_offset = self.hands.offset_seconds();
+            self.settings.save()?;
+        }
         Ok(())
     }
 
-    pub fn toggle_light(&mut self) -> Result<()> {
+    fn toggle_light(&mut self) -> Result<()> {
+        if self.model.light() == Light::None {
+            return Ok(());
+        }
         self.light_on = !self.light_on;
         if self.light_on {
             self.light_start_time = Some(Instant::now());
@@ -225,11 +2168,29 @@ impl Watch {
         Ok(())
     }
 
-    pub fn set_alarm(&mut self) -> Result<()> {
+    fn set_alarm(&mut self) -> Result<()> {
+        // the extra alarms get the same one-minute test time as the main one
+        if self.model.module() == Module::W800H
+            && self.w800h_mode == W800HMode::Alarm
+            && self.alarm_slot > 0
+        {
+            let now = self.time_manager.current_time;
+            if let Some(alarm) = self.settings.extra_alarms.get_mut(self.alarm_slot - 1) {
+                alarm.enabled = !alarm.enabled;
+                alarm.time = alarm.enabled.then(|| {
+                    (now + chrono::Duration::minutes(1))
+                        .format("%H:%M")
+                        .to_string()
+                });
+                self.settings.save()?;
+            }
+            return Ok(());
+        }
+
         self.settings.alarm_enabled = !self.settings.alarm_enabled;
         if self.settings.alarm_enabled {
             // Set alarm for 1 minute from now for testing
-            let now = chrono::Local::now();
+            let now = self.time_manager.current_time;
             let alarm_time = now + chrono::Duration::minutes(1);
             self.settings.alarm_time = Some(alarm_time.format("%H:%M").to_string());
         } else {
@@ -239,3 +2200,83 @@ impl Watch {
         Ok(())
     }
 }
+
+// the W-800H has three alarms whatever the profile was saved with
+fn fit_alarm_slots(model: WatchModel, settings: &mut WatchSettings) {
+    if model.module() == Module::W800H {
+        settings
+            .extra_alarms
+            .resize(W800H_ALARMS - 1, DailyAlarm::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manual::Manual;
+    use chrono::TimeZone;
+    use std::fs;
+
+    // walks every built-in model round its modes, pressing each button its table lists
+    // presses every button but mode once, checking the manual has them all first
+    fn press_what_the_manual_lists(watch: &mut Watch) {
+        let manual = Manual::new(watch);
+        let controls = watch.controls();
+        let labels: Vec<_> = controls
+            .iter()
+            .map(|(button, control)| (*button, control.label()))
+            .collect();
+        assert_eq!(manual.controls, labels, "{}", watch.model.name());
+
+        for (index, (button, _)) in controls.iter().enumerate() {
+            assert!(
+                !controls[..index].iter().any(|(other, _)| other == button),
+                "{} has two controls on {}",
+                watch.model.name(),
+                button.name()
+            );
+            if *button != Button::Mode {
+                watch.press(*button).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn every_mode_presses_what_the_manual_lists() {
+        let path =
+            std::env::temp_dir().join(format!("casiotoy-controls-{}.json", std::process::id()));
+        let start = Local.with_ymd_and_hms(2026, 3, 14, 9, 0, 0).unwrap();
+        for model in WatchModel::ALL {
+            let settings = WatchSettings::load(&path, model.name()).unwrap();
+            let time_manager = TimeManager::starting_at(start, 1.0);
+            let mut watch = Watch::new(model, settings, time_manager, Peripherals::None).unwrap();
+            let modes = Manual::new(&watch).modes.len();
+            let mut seen = Vec::new();
+
+            for _ in 0..modes.max(1) {
+                let manual = Manual::new(&watch);
+                seen.extend(manual.current);
+                press_what_the_manual_lists(&mut watch);
+                // the w-800h alarm mode has a screen per alarm and one for the signal
+                while watch.alarm_slot != 0 {
+                    if watch.alarm_slot == W800H_ALARMS {
+                        assert!(!watch.controls().contains(&ALARM_CONTROL));
+                    }
+                    press_what_the_manual_lists(&mut watch);
+                }
+                // telememo input and calibration hold on to the mode button for a press
+                for _ in 0..2 {
+                    watch.press(Button::Mode).unwrap();
+                    if Manual::new(&watch).current != manual.current {
+                        break;
+                    }
+                }
+            }
+
+            seen.sort();
+            seen.dedup();
+            assert_eq!(seen.len(), modes, "{} skipped a mode", model.name());
+        }
+        fs::remove_file(&path).unwrap();
+    }
+}